@@ -0,0 +1,110 @@
+use accesskit::{Live, Node, Role, Toggled};
+use bevy::{a11y::AccessibilityNode, prelude::*};
+
+use crate::{
+    interaction::{Interactable, State},
+    localization::Localization,
+};
+
+// Accessibility options.
+#[derive(Clone, Debug, Default, Resource)]
+pub struct AccessibilitySettings {
+    pub reduced_motion: bool,
+    pub high_contrast: bool,
+}
+
+// Live region read out by screen readers when something in the scene changes.
+// NOTE: AccessKit has no web backend yet so this only reaches native screen readers.
+#[derive(Component)]
+struct Announcer;
+
+// Message sent to announce text to assistive technology.
+#[derive(Message)]
+pub struct Announcement(pub String);
+
+// Speed and amplitude multipliers for flickering lights with reduced motion.
+pub const REDUCED_FLICKER_SPEED: f32 = 0.1;
+pub const REDUCED_FLICKER_AMPLITUDE: f32 = 0.25;
+
+// Speed multiplier for particles, like the snow, with reduced motion.
+pub const REDUCED_PARTICLE_SPEED: f32 = 0.3;
+
+// Add the accessibility systems.
+pub fn add_systems(app: &mut App) {
+    app.init_resource::<AccessibilitySettings>()
+        .add_message::<Announcement>()
+        .add_systems(Startup, init)
+        .add_systems(
+            Update,
+            (
+                handle_interactable_nodes,
+                handle_state_announcements,
+                handle_announcements.after(handle_state_announcements),
+            ),
+        );
+}
+
+// Translated name for an interactable id.
+pub fn display_name(localization: &Localization, id: &str) -> String {
+    localization.get(&format!("name.{id}")).to_string()
+}
+
+// Update the live region with the latest announcement.
+fn handle_announcements(
+    mut events: MessageReader<Announcement>,
+    mut query: Query<&mut AccessibilityNode, With<Announcer>>,
+) {
+    let Some(Announcement(text)) = events.read().last() else {
+        return;
+    };
+
+    for mut node in &mut query {
+        node.set_label(text.as_str());
+    }
+}
+
+// Describe interactables to assistive technology as switches, relabeling them all when the language changes.
+fn handle_interactable_nodes(
+    mut commands: Commands,
+    localization: Res<Localization>,
+    query: Query<(Entity, Ref<Interactable>, Ref<State>)>,
+) {
+    for (entity, interactable, state) in &query {
+        if !localization.is_changed() && !interactable.is_added() && !state.is_changed() {
+            continue;
+        }
+
+        let mut node = Node::new(Role::Switch);
+        node.set_label(display_name(&localization, &interactable.id));
+        node.set_toggled(Toggled::from(*state == State::On));
+        commands.entity(entity).insert(AccessibilityNode(node));
+    }
+}
+
+// Announce interactables being turned on or off.
+fn handle_state_announcements(
+    localization: Res<Localization>,
+    query: Query<(&Interactable, Ref<State>), Changed<State>>,
+    mut announcements: MessageWriter<Announcement>,
+) {
+    for (interactable, state) in &query {
+        // Skip the initial state of newly spawned interactables.
+        if state.is_added() {
+            continue;
+        }
+
+        let key = match *state {
+            State::On => "announce.turned_on",
+            State::Off => "announce.turned_off",
+        };
+        let name = display_name(&localization, &interactable.id);
+        announcements.write(Announcement(localization.format(key, &[("name", &name)])));
+    }
+}
+
+// Accessibility initialization.
+fn init(mut commands: Commands) {
+    let mut node = Node::new(Role::Status);
+    node.set_live(Live::Polite);
+    commands.spawn((Announcer, AccessibilityNode(node)));
+}
@@ -0,0 +1,350 @@
+use bevy::{ecs::system::SystemParam, prelude::*, time::Stopwatch};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{
+    accessibility::Announcement,
+    data,
+    dialogue::DialogueState,
+    input::InputEvent,
+    interaction::InteractionEvent,
+    localization::Localization,
+    menu::MenuState,
+    santa::{AddPresentsEvent, Gift, SantasHereEvent},
+    timeline::CutsceneState,
+};
+
+// Goals to discover in the card, authored as data in "achievements/card.goals.ron".
+#[derive(Asset, TypePath, Deserialize)]
+#[serde(transparent)]
+struct Goals(Vec<Goal>);
+
+// A goal unlocked by something happening a number of times, the title is the localization key `achievement.{id}`.
+#[derive(Deserialize)]
+struct Goal {
+    id: String,
+    trigger: Trigger,
+    #[serde(default = "count_default")]
+    count: u32,
+    // Localization key for a nudge shown to idle users while the goal is locked.
+    #[serde(default)]
+    hint: Option<String>,
+}
+
+// Things that happen in the card that goals count.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+enum Trigger {
+    Interact(String),
+    SantasHere,
+    Presents(Gift),
+}
+
+// Goals unlocked so far and progress on the rest, both kept between visits.
+// NOTE: progress is a `BTreeMap` so the saved file doesn't shuffle from one save to the next.
+#[derive(Resource, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Achievements {
+    unlocked: Vec<String>,
+    progress: BTreeMap<String, u32>,
+}
+
+// Message sent to show a toast with a localization key, formatted with the achievement title if given.
+#[derive(Message)]
+struct ToastEvent {
+    key: String,
+    achievement: Option<String>,
+}
+
+// Column of toasts in the corner of the screen.
+#[derive(Component)]
+struct Toasts;
+
+// Short lived message about an unlocked achievement or a hint.
+#[derive(Component)]
+struct Toast(Timer);
+
+// Time since the user last did something and the next hint to show.
+#[derive(Resource, Default)]
+struct Hints {
+    idle: Stopwatch,
+    next: usize,
+}
+
+#[derive(Resource)]
+struct AchievementAssets {
+    goals: Handle<Goals>,
+}
+
+// Everything goals listen to.
+#[derive(SystemParam)]
+struct Triggers<'w, 's> {
+    interactions: MessageReader<'w, 's, InteractionEvent>,
+    santa: MessageReader<'w, 's, SantasHereEvent>,
+    presents: MessageReader<'w, 's, AddPresentsEvent>,
+}
+
+// Seconds without input before a hint is shown.
+const HINT_SECONDS: f32 = 45.0;
+
+// Seconds a toast stays up, fading out over the last second.
+const TOAST_SECONDS: f32 = 5.0;
+const TOAST_FADE_SECONDS: f32 = 1.0;
+
+const FONT_SIZE: f32 = 9.0;
+const TOAST_COLOR: Color = Color::srgba(0.05, 0.05, 0.1, 0.85);
+const ACHIEVEMENT_COLOR: Color = Color::srgb(1.0, 0.85, 0.4);
+const HINT_COLOR: Color = Color::srgb(0.8, 0.85, 1.0);
+
+#[cfg(target_arch = "wasm32")]
+const STORAGE_KEY: &str = "holiday_card.achievements";
+
+#[cfg(not(target_arch = "wasm32"))]
+const SAVE_FILE: &str = ".holiday_card_achievements.ron";
+
+fn count_default() -> u32 {
+    1
+}
+
+impl Triggers<'_, '_> {
+    // Everything that happened since the last read.
+    fn read(&mut self) -> Vec<Trigger> {
+        let interactions = self
+            .interactions
+            .read()
+            .map(|event| Trigger::Interact(event.id.clone()));
+        let santa = self.santa.read().map(|_| Trigger::SantasHere);
+        let presents = self.presents.read().map(|event| Trigger::Presents(event.0));
+
+        interactions.chain(santa).chain(presents).collect()
+    }
+}
+
+impl Achievements {
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.unlocked.iter().any(|unlocked| unlocked == id)
+    }
+}
+
+// Add the achievement systems.
+pub fn add_systems(app: &mut App) {
+    data::register::<Goals>(app, &["goals.ron"]);
+
+    app.init_resource::<Achievements>()
+        .init_resource::<Hints>()
+        .add_message::<ToastEvent>()
+        .add_systems(Startup, init)
+        .add_systems(
+            Update,
+            (
+                handle_triggers,
+                handle_hints.run_if(
+                    in_state(MenuState::Closed)
+                        .and(in_state(CutsceneState::Idle))
+                        .and(in_state(DialogueState::Closed)),
+                ),
+                handle_toasts,
+                handle_toast_fade,
+            )
+                .chain(),
+        );
+}
+
+// Load the saved achievements from the browser's local storage.
+#[cfg(target_arch = "wasm32")]
+fn load() -> Option<String> {
+    web_sys::window()?.local_storage().ok()??.get_item(STORAGE_KEY).ok()?
+}
+
+// Save the achievements to the browser's local storage.
+#[cfg(target_arch = "wasm32")]
+fn save(data: &str) {
+    if let Some(Ok(Some(storage))) = web_sys::window().map(|window| window.local_storage()) {
+        let _ = storage.set_item(STORAGE_KEY, data);
+    }
+}
+
+// Path of the save file in the user's home directory.
+#[cfg(not(target_arch = "wasm32"))]
+fn save_path() -> std::path::PathBuf {
+    std::env::var_os("HOME")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_default()
+        .join(SAVE_FILE)
+}
+
+// Load the saved achievements from disk.
+#[cfg(not(target_arch = "wasm32"))]
+fn load() -> Option<String> {
+    std::fs::read_to_string(save_path()).ok()
+}
+
+// Save the achievements to disk.
+#[cfg(not(target_arch = "wasm32"))]
+fn save(data: &str) {
+    if let Err(error) = std::fs::write(save_path(), data) {
+        warn!("couldn't save achievements: {error}");
+    }
+}
+
+// Nudge users who haven't done anything for a while toward a goal they haven't found.
+fn handle_hints(
+    time: Res<Time>,
+    assets: Res<AchievementAssets>,
+    goals: Res<Assets<Goals>>,
+    achievements: Res<Achievements>,
+    mut inputs: MessageReader<InputEvent>,
+    mut hints: ResMut<Hints>,
+    mut toast_events: MessageWriter<ToastEvent>,
+) {
+    if inputs.read().count() > 0 {
+        hints.idle.reset();
+        return;
+    }
+
+    hints.idle.tick(time.delta());
+    if hints.idle.elapsed_secs() < HINT_SECONDS {
+        return;
+    }
+    hints.idle.reset();
+
+    // Cycle through the hints for locked goals so the same one isn't repeated.
+    let Some(goals) = goals.get(&assets.goals) else {
+        return;
+    };
+    let locked: Vec<&String> = goals
+        .0
+        .iter()
+        .filter(|goal| !achievements.is_unlocked(&goal.id))
+        .filter_map(|goal| goal.hint.as_ref())
+        .collect();
+    if locked.is_empty() {
+        return;
+    }
+
+    let hint = locked[hints.next % locked.len()];
+    hints.next = hints.next.wrapping_add(1);
+    toast_events.write(ToastEvent {
+        key: hint.clone(),
+        achievement: None,
+    });
+}
+
+// Fade out and remove old toasts.
+fn handle_toast_fade(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut toasts: Query<(Entity, &mut Toast, &mut BackgroundColor, &Children)>,
+    mut texts: Query<&mut TextColor>,
+) {
+    for (entity, mut toast, mut background, children) in &mut toasts {
+        toast.0.tick(time.delta());
+        if toast.0.is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let alpha = (toast.0.remaining_secs() / TOAST_FADE_SECONDS).min(1.0);
+        background.0.set_alpha(TOAST_COLOR.alpha() * alpha);
+        let mut texts = texts.iter_many_mut(children);
+        while let Some(mut color) = texts.fetch_next() {
+            color.0.set_alpha(alpha);
+        }
+    }
+}
+
+// Show toasts for unlocked achievements and hints, and read them out to assistive technology.
+fn handle_toasts(
+    mut commands: Commands,
+    localization: Res<Localization>,
+    mut events: MessageReader<ToastEvent>,
+    mut announcements: MessageWriter<Announcement>,
+    toasts: Single<Entity, With<Toasts>>,
+) {
+    for event in events.read() {
+        let (text, color) = match &event.achievement {
+            Some(id) => {
+                let name = localization.get(&format!("achievement.{id}")).to_string();
+                (localization.format(&event.key, &[("name", &name)]), ACHIEVEMENT_COLOR)
+            }
+            None => (localization.get(&event.key).to_string(), HINT_COLOR),
+        };
+
+        announcements.write(Announcement(text.clone()));
+        commands.entity(*toasts).with_child((
+            Toast(Timer::from_seconds(TOAST_SECONDS, TimerMode::Once)),
+            Node {
+                max_width: px(160),
+                padding: UiRect::axes(px(4), px(2)),
+                ..default()
+            },
+            BackgroundColor(TOAST_COLOR),
+            children![(Text::new(text), TextFont::from_font_size(FONT_SIZE), TextColor(color))],
+        ));
+    }
+}
+
+// Count triggers toward goals and unlock the ones that are reached.
+// NOTE: messages only last a couple of frames, so triggers are held on to until the goals have loaded.
+fn handle_triggers(
+    assets: Res<AchievementAssets>,
+    goals: Res<Assets<Goals>>,
+    mut triggers: Triggers,
+    mut pending: Local<Vec<Trigger>>,
+    mut achievements: ResMut<Achievements>,
+    mut toast_events: MessageWriter<ToastEvent>,
+) {
+    pending.extend(triggers.read());
+    let Some(goals) = goals.get(&assets.goals) else {
+        return;
+    };
+
+    let mut counted = false;
+    for trigger in pending.drain(..) {
+        for goal in goals.0.iter().filter(|goal| goal.trigger == trigger) {
+            if achievements.is_unlocked(&goal.id) {
+                continue;
+            }
+
+            let progress = achievements.progress.entry(goal.id.clone()).or_default();
+            *progress += 1;
+            counted = true;
+            if *progress >= goal.count {
+                achievements.unlocked.push(goal.id.clone());
+                toast_events.write(ToastEvent {
+                    key: "achievement.unlocked".to_string(),
+                    achievement: Some(goal.id.clone()),
+                });
+            }
+        }
+    }
+
+    if counted && let Ok(data) = ron::to_string(&*achievements) {
+        save(&data);
+    }
+}
+
+// Load the goals and saved progress, and make room for toasts in the lower left corner.
+// NOTE: toasts aren't part of the scene so they stay up when the card is reset.
+fn init(mut commands: Commands, asset_server: Res<AssetServer>, mut achievements: ResMut<Achievements>) {
+    commands.insert_resource(AchievementAssets {
+        goals: asset_server.load("achievements/card.goals.ron"),
+    });
+
+    if let Some(saved) = load().and_then(|data| ron::from_str(&data).ok()) {
+        *achievements = saved;
+    }
+
+    commands.spawn((
+        Toasts,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: percent(3),
+            left: percent(2),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Start,
+            row_gap: px(2),
+            ..default()
+        },
+        GlobalZIndex(6),
+    ));
+}
@@ -2,8 +2,8 @@ use bevy::prelude::*;
 use std::time::Duration;
 
 use crate::{
-    background, chair, fireplace, flickering_light, house, house_lights, interaction, santa, snow, snowman, stereo,
-    theman, tree,
+    background, chair, fireplace, flickering_light, house, house_lights, interaction, particles, santa, sequencer, sky,
+    snow, snowman, stereo, surface, theman, tree, warmth, wiring,
 };
 
 #[derive(Component)]
@@ -38,10 +38,16 @@ pub fn add_systems(app: &mut App) {
     flickering_light::add_systems(app);
     house::add_systems(app);
     fireplace::add_systems(app);
+    particles::add_systems(app);
     santa::add_systems(app);
+    sequencer::add_systems(app);
+    sky::add_systems(app);
     snow::add_systems(app);
     snowman::add_systems(app);
     stereo::add_systems(app);
+    surface::add_systems(app);
     theman::add_systems(app);
     tree::add_systems(app);
+    warmth::add_systems(app);
+    wiring::add_systems(app);
 }
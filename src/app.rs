@@ -4,23 +4,63 @@ use bevy::{
 };
 use bevy_light_2d::prelude::*;
 
-use crate::{animation, camera, input};
+use crate::{
+    accessibility, achievements, animation, camera, dialogue, input, localization, menu, rooms, settings, timeline,
+    tooltip,
+};
 
 const AUDIO_SCALE: f32 = 1. / 200.;
 
+// Top level card state, resetting the card despawns the scene and runs every initialization again.
+#[derive(States, Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum CardState {
+    #[default]
+    Running,
+    Resetting,
+}
+
 pub fn run_app() {
     let mut app = App::new();
 
     app.add_plugins((
-        DefaultPlugins.set(ImagePlugin::default_nearest()).set(AudioPlugin {
-            default_spatial_scale: SpatialScale::new_2d(AUDIO_SCALE),
-            ..default()
-        }),
+        DefaultPlugins
+            .set(ImagePlugin::default_nearest())
+            .set(AudioPlugin {
+                default_spatial_scale: SpatialScale::new_2d(AUDIO_SCALE),
+                ..default()
+            })
+            .set(WindowPlugin {
+                // Let the page decide the canvas size, the camera frames the world to fit.
+                primary_window: Some(Window {
+                    fit_canvas_to_parent: true,
+                    ..default()
+                }),
+                ..default()
+            }),
         Light2dPlugin,
     ));
+    app.init_state::<CardState>()
+        .add_systems(OnEnter(CardState::Resetting), handle_reset);
+
+    settings::add_systems(&mut app);
+    accessibility::add_systems(&mut app);
+    localization::add_systems(&mut app);
+    menu::add_systems(&mut app);
     camera::add_systems(&mut app);
+    rooms::add_systems(&mut app);
+    timeline::add_systems(&mut app);
+    dialogue::add_systems(&mut app);
+    tooltip::add_systems(&mut app);
+    achievements::add_systems(&mut app);
     input::add_systems(&mut app);
     animation::add_systems(&mut app);
+    #[cfg(feature = "dev")]
+    crate::debug::add_systems(&mut app);
 
     app.run();
 }
+
+// Start the card again once the old scene has been despawned.
+fn handle_reset(mut next_state: ResMut<NextState<CardState>>) {
+    next_state.set(CardState::Running);
+}
@@ -1,9 +1,17 @@
 use bevy::prelude::*;
 use bevy_light_2d::prelude::*;
 
+use crate::app::CardState;
+
 #[derive(Component)]
 struct Background;
 
+// Light from the moon, dimmed by the sky from its full intensity.
+#[derive(Component)]
+pub struct Moonlight {
+    pub intensity: f32,
+}
+
 #[derive(Component)]
 struct SnowMovement {
     timer: Timer,
@@ -11,9 +19,12 @@ struct SnowMovement {
     progress: f32,
 }
 
+const MOONLIGHT_INTENSITY: f32 = 0.4;
+
 // Add the animation systems.
 pub fn add_systems(app: &mut App) {
-    app.add_systems(Startup, init).add_systems(Update, handle_snow);
+    app.add_systems(OnEnter(CardState::Running), init)
+        .add_systems(Update, handle_snow);
 }
 
 // Handle the snow rising over time.
@@ -41,6 +52,7 @@ fn init(mut commands: Commands, asset_server: Res<AssetServer>) {
             ..default()
         },
         Transform::from_xyz(0.0, 0.0, 0.0),
+        DespawnOnExit(CardState::Running),
         Background,
     ));
 
@@ -52,6 +64,7 @@ fn init(mut commands: Commands, asset_server: Res<AssetServer>) {
             ..default()
         },
         Transform::from_xyz(0.0, -75.0, 1.5),
+        DespawnOnExit(CardState::Running),
         Background,
         SnowMovement {
             timer: Timer::from_seconds(60.0 * 5.0, TimerMode::Once),
@@ -64,7 +77,7 @@ fn init(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands.spawn((
         SpotLight2d {
             color: Color::srgba(1.0, 1.0, 1.0, 1.0),
-            intensity: 0.4,
+            intensity: MOONLIGHT_INTENSITY,
             radius: 200.0,
             direction: 135.0,
             inner_angle: 40.0,
@@ -74,5 +87,9 @@ fn init(mut commands: Commands, asset_server: Res<AssetServer>) {
             ..default()
         },
         Transform::from_xyz(-160.0, 140.0, 2.0),
+        DespawnOnExit(CardState::Running),
+        Moonlight {
+            intensity: MOONLIGHT_INTENSITY,
+        },
     ));
 }
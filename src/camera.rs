@@ -1,29 +1,362 @@
-use bevy::{camera::ScalingMode, prelude::*};
+use bevy::{
+    camera::{ScalingMode, Viewport},
+    prelude::*,
+    window::PrimaryWindow,
+};
 use bevy_light_2d::prelude::*;
+use rand::Rng;
 
-const WINDOW_HEIGHT: f32 = 150.0;
-const WINDOW_WIDTH: f32 = 300.0;
+use crate::{
+    accessibility::AccessibilitySettings,
+    dialogue::DialogueState,
+    localization::{Localization, Localized},
+    settings::{KeyBindings, Settings},
+    theman::TheMan,
+};
+
+// How the scene is framed inside the window, picked in the settings menu.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Framing {
+    // Letterbox in landscape and follow the man in portrait.
+    #[default]
+    Auto,
+    // Stretch the whole scene over the window.
+    Fixed,
+    // Show the whole scene at an integer scale with bars around it.
+    Letterbox,
+    // Fill the window height at an integer scale and follow the man sideways.
+    Follow,
+}
+
+// Moves the camera to follow the man, zoom in on points of interest and shake.
+#[derive(Component)]
+pub struct CameraController {
+    // Unzoomed world size shown in the viewport and its physical pixels per world unit.
+    view_size: Vec2,
+    scale: f32,
+    // Camera center before shaking and the current zoom.
+    position: Vec2,
+    zoom: f32,
+    focus: Option<Focus>,
+    shake: Option<Shake>,
+}
+
+struct Focus {
+    target: Vec2,
+    zoom: f32,
+    timer: Timer,
+}
+
+struct Shake {
+    strength: f32,
+    timer: Timer,
+}
+
+// Area of the world the camera is allowed to show.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct CameraBounds(pub Rect);
+
+// Help text listing the keys that move the man.
+#[derive(Component)]
+struct MoveHelp;
+
+// The man, for the camera to follow.
+type Man<'w, 's> = Option<Single<'w, 's, &'static Transform, (With<TheMan>, Without<CameraController>)>>;
+
+// Message sent to zoom the camera in on a point for a while.
+#[derive(Message)]
+pub struct ZoomEvent {
+    pub target: Vec2,
+    pub zoom: f32,
+    pub duration: f32,
+}
+
+// Message sent to shake the camera, fading out over the duration.
+#[derive(Message)]
+pub struct ShakeEvent {
+    pub strength: f32,
+    pub duration: f32,
+}
+
+pub const WORLD_HEIGHT: f32 = 150.0;
+pub const WORLD_WIDTH: f32 = 300.0;
+
+// Narrowest slice of the world shown when following the man.
+const FOLLOW_MIN_WIDTH: f32 = 120.0;
+
+// World scale the UI sizes were designed for, in logical pixels per world unit.
+const UI_REFERENCE_SCALE: f32 = 4.0;
+
+// How far the man can move from the camera center before it follows, in world units.
+const DEAD_ZONE: Vec2 = Vec2::new(24.0, 32.0);
+
+// Rate the camera catches up with its target, higher is snappier.
+const FOLLOW_SPEED: f32 = 3.0;
+const ZOOM_SPEED: f32 = 2.0;
+
+const FRAMINGS: [Framing; 4] = [Framing::Auto, Framing::Fixed, Framing::Letterbox, Framing::Follow];
 
 const AMBIENT_BRIGHTNESS: f32 = 0.035;
+const HIGH_CONTRAST_BRIGHTNESS: f32 = 0.3;
+
+impl Default for CameraBounds {
+    fn default() -> Self {
+        Self(world_rect())
+    }
+}
+
+impl Framing {
+    // Key of the framing's translated name.
+    pub const fn key(self) -> &'static str {
+        match self {
+            Self::Auto => "framing.auto",
+            Self::Fixed => "framing.fixed",
+            Self::Letterbox => "framing.letterbox",
+            Self::Follow => "framing.follow",
+        }
+    }
+
+    // Cycle through the framing modes.
+    pub fn step(self, step: i32) -> Self {
+        let index = FRAMINGS.iter().position(|&framing| framing == self).unwrap_or(0);
+        FRAMINGS[(index as i32 + step).rem_euclid(FRAMINGS.len() as i32) as usize]
+    }
+
+    // Resolve automatic framing for a window size.
+    fn resolve(self, window_size: Vec2) -> Self {
+        match self {
+            Self::Auto if window_size.y > window_size.x => Self::Follow,
+            Self::Auto => Self::Letterbox,
+            framing => framing,
+        }
+    }
+}
 
 // Add the camera systems.
 pub fn add_systems(app: &mut App) {
-    app.add_systems(Startup, init);
+    app.init_resource::<CameraBounds>()
+        .add_message::<ZoomEvent>()
+        .add_message::<ShakeEvent>()
+        .add_systems(Startup, init)
+        .add_systems(
+            Update,
+            (handle_framing, handle_events, handle_ambient_light, handle_help),
+        )
+        .add_systems(PostUpdate, handle_controller.before(TransformSystems::Propagate));
+}
+
+// Clamp a camera center so a view of the given size stays inside the bounds, centering it when it doesn't fit.
+fn clamp_to_bounds(center: Vec2, size: Vec2, bounds: Rect) -> Vec2 {
+    let min = bounds.min + size / 2.0;
+    let max = bounds.max - size / 2.0;
+    let clamp = |value: f32, min: f32, max: f32, middle: f32| {
+        if min <= max { value.clamp(min, max) } else { middle }
+    };
+
+    Vec2::new(
+        clamp(center.x, min.x, max.x, bounds.center().x),
+        clamp(center.y, min.y, max.y, bounds.center().y),
+    )
+}
+
+// The whole world, the camera never shows past its edges.
+fn world_rect() -> Rect {
+    Rect::from_center_size(Vec2::ZERO, Vec2::new(WORLD_WIDTH, WORLD_HEIGHT))
+}
+
+// Round a scale down to whole pixels, small windows that can't fit one pixel per unit keep the fraction.
+fn integer_scale(scale: f32) -> f32 {
+    if scale >= 1.0 { scale.floor() } else { scale }
+}
+
+// Set the ambient light, boosted for low-vision users in high contrast mode.
+fn handle_ambient_light(accessibility: Res<AccessibilitySettings>, mut query: Query<&mut Light2d>) {
+    if !accessibility.is_changed() {
+        return;
+    }
+
+    for mut light in &mut query {
+        light.ambient_light.brightness = if accessibility.high_contrast {
+            HIGH_CONTRAST_BRIGHTNESS
+        } else {
+            AMBIENT_BRIGHTNESS
+        };
+    }
+}
+
+// Show the keys the man is moved with in the help text, they can be changed in the settings menu.
+fn handle_help(settings: Res<Settings>, localization: Res<Localization>, mut text: Single<&mut Text, With<MoveHelp>>) {
+    if !localization.is_loaded() || (!settings.is_changed() && !localization.is_changed()) {
+        return;
+    }
+
+    let KeyBindings { left, right, interact } = settings.bindings;
+    text.0 = localization.format(
+        "help.move",
+        &[
+            ("left", &format!("{left:?}")),
+            ("right", &format!("{right:?}")),
+            ("interact", &format!("{interact:?}")),
+        ],
+    );
+}
+
+// Size the camera viewport and projection for the window, framing mode and the scene inside the camera bounds.
+fn handle_framing(
+    settings: Res<Settings>,
+    bounds: Res<CameraBounds>,
+    window: Single<Ref<Window>, With<PrimaryWindow>>,
+    mut camera: Single<(&mut Camera, &mut Projection, &mut CameraController)>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    if !window.is_changed() && !settings.is_changed() && !bounds.is_changed() {
+        return;
+    }
+
+    let window_size = window.physical_size().as_vec2();
+    if window_size.min_element() <= 0.0 {
+        return;
+    }
+
+    let scene = bounds.0.size();
+    let framing = settings.framing;
+    let (scale, view_size) = match framing.resolve(window_size) {
+        Framing::Fixed => (window_size.y / scene.y, scene),
+        Framing::Letterbox => (integer_scale((window_size / scene).min_element()), scene),
+        Framing::Follow | Framing::Auto => {
+            let scale = integer_scale((window_size.y / scene.y).min(window_size.x / FOLLOW_MIN_WIDTH));
+            (scale, Vec2::new((window_size.x / scale).min(scene.x), scene.y))
+        }
+    };
+
+    let (camera, projection, controller) = &mut *camera;
+
+    // Fixed framing stretches over the whole window, the others render an exact multiple of the scene size.
+    camera.viewport = (framing != Framing::Fixed).then(|| {
+        let physical_size = (view_size * scale).round().min(window_size).as_uvec2();
+        Viewport {
+            physical_position: (window.physical_size() - physical_size) / 2,
+            physical_size,
+            ..default()
+        }
+    });
+
+    if let Projection::Orthographic(ortho) = &mut **projection {
+        ortho.scaling_mode = ScalingMode::Fixed {
+            width: view_size.x,
+            height: view_size.y,
+        };
+    }
+
+    controller.view_size = view_size;
+    controller.scale = scale;
+    ui_scale.0 = scale / window.scale_factor() / UI_REFERENCE_SCALE;
+}
+
+// Follow the man, zoom and shake, keeping the view inside the camera bounds.
+// NOTE: a zoom holds while a dialogue is showing, the timeline that started it waits for the dialogue too.
+fn handle_controller(
+    time: Res<Time>,
+    dialogue_state: Res<State<DialogueState>>,
+    bounds: Res<CameraBounds>,
+    man: Man,
+    mut camera: Single<(&mut CameraController, &mut Transform, &mut Projection)>,
+) {
+    let (controller, transform, projection) = &mut *camera;
+
+    if let Some(focus) = &mut controller.focus
+        && *dialogue_state.get() == DialogueState::Closed
+        && focus.timer.tick(time.delta()).is_finished()
+    {
+        controller.focus = None;
+    }
+
+    // Look at the focus point, or move just enough to keep the man inside the dead zone.
+    let (target, zoom) = match &controller.focus {
+        Some(focus) => (focus.target, focus.zoom),
+        None => {
+            let man = man.map_or(controller.position, |man| man.translation.truncate());
+            let offset = man - controller.position;
+            (controller.position + offset - offset.clamp(-DEAD_ZONE, DEAD_ZONE), 1.0)
+        }
+    };
+
+    let delta = time.delta_secs();
+    controller.zoom = controller.zoom.lerp(zoom, 1.0 - (-ZOOM_SPEED * delta).exp());
+    let size = controller.view_size / controller.zoom;
+    let clamp = |center| clamp_to_bounds(center, size, bounds.0);
+    let target = clamp(target);
+    controller.position = clamp(controller.position.lerp(target, 1.0 - (-FOLLOW_SPEED * delta).exp()));
+
+    let mut offset = Vec2::ZERO;
+    if let Some(shake) = &mut controller.shake {
+        shake.timer.tick(time.delta());
+        let angle = rand::rng().random_range(0.0..std::f32::consts::TAU);
+        offset = Vec2::from_angle(angle) * shake.strength * shake.timer.fraction_remaining();
+        if shake.timer.is_finished() {
+            controller.shake = None;
+        }
+    }
+    // Snap to whole screen pixels so the pixel art doesn't shimmer.
+    let scale = controller.scale * controller.zoom;
+    let center = ((controller.position + offset) * scale).round() / scale;
+
+    transform.translation.x = center.x;
+    transform.translation.y = center.y;
+    if let Projection::Orthographic(ortho) = &mut **projection {
+        ortho.scale = 1.0 / controller.zoom;
+    }
+}
+
+// Start zooms and shakes requested by other systems.
+// NOTE: reduced motion skips both, the camera only follows the man.
+fn handle_events(
+    accessibility: Res<AccessibilitySettings>,
+    mut zoom_events: MessageReader<ZoomEvent>,
+    mut shake_events: MessageReader<ShakeEvent>,
+    mut controller: Single<&mut CameraController>,
+) {
+    for event in zoom_events.read() {
+        if !accessibility.reduced_motion {
+            controller.focus = Some(Focus {
+                target: event.target,
+                zoom: event.zoom,
+                timer: Timer::from_seconds(event.duration, TimerMode::Once),
+            });
+        }
+    }
+
+    for event in shake_events.read() {
+        if !accessibility.reduced_motion {
+            controller.shake = Some(Shake {
+                strength: event.strength,
+                timer: Timer::from_seconds(event.duration, TimerMode::Once),
+            });
+        }
+    }
 }
 
 // Camera initialization.
 fn init(mut commands: Commands) {
-    // Create the camera projection.
+    // Create the camera projection, resized to the window by `handle_framing`.
     let mut ortho = OrthographicProjection::default_2d();
     ortho.scaling_mode = ScalingMode::Fixed {
-        width: WINDOW_WIDTH,
-        height: WINDOW_HEIGHT,
+        width: WORLD_WIDTH,
+        height: WORLD_HEIGHT,
     };
     let projection = Projection::Orthographic(ortho);
 
     commands.spawn((
         Camera2d,
         projection,
+        CameraController {
+            view_size: Vec2::new(WORLD_WIDTH, WORLD_HEIGHT),
+            scale: 1.0,
+            position: Vec2::ZERO,
+            zoom: 1.0,
+            focus: None,
+            shake: None,
+        },
         Light2d {
             ambient_light: AmbientLight2d {
                 brightness: AMBIENT_BRIGHTNESS,
@@ -32,23 +365,26 @@ fn init(mut commands: Commands) {
         },
     ));
 
-    // Display help UI in the upper right.
-    commands.spawn((
-        Text::new("move: left/right - interact: up"),
-        Node {
-            position_type: PositionType::Absolute,
-            top: px(12),
-            right: px(12),
-            ..default()
-        },
-    ));
+    // Display help UI in the upper right of the visible world.
     commands.spawn((
-        Text::new("or click to move and interact"),
         Node {
             position_type: PositionType::Absolute,
-            top: px(35),
-            right: px(24),
+            top: percent(3),
+            right: percent(2),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::End,
             ..default()
         },
+        children![
+            (Text::new("move: left/right - interact: up"), MoveHelp),
+            (
+                Text::new("or click to move and interact"),
+                Localized("help.click"),
+                Node {
+                    margin: UiRect::right(px(12)),
+                    ..default()
+                },
+            ),
+        ],
     ));
 }
@@ -1,6 +1,11 @@
 use bevy::prelude::*;
 
-use crate::interaction::{Interactable, State};
+use crate::{
+    app::CardState,
+    interaction::{Interactable, State, StateSet},
+    rooms::{InRoom, Room},
+    theman::{Sitting, TheMan},
+};
 
 #[derive(Component)]
 struct Chair;
@@ -9,7 +14,16 @@ pub const INTERACTABLE_ID: &str = "chair";
 
 // Add the animation systems.
 pub fn add_systems(app: &mut App) {
-    app.add_systems(Startup, init);
+    app.add_systems(OnEnter(CardState::Running), init)
+        .add_systems(Update, handle_sitting.in_set(StateSet::Change));
+}
+
+// Turn the chair on while the man is sitting in it, so its prompt offers to get up.
+fn handle_sitting(man: Single<Has<Sitting>, With<TheMan>>, mut query: Query<&mut State, With<Chair>>) {
+    let state = if *man { State::On } else { State::Off };
+    for mut chair in &mut query {
+        chair.set_if_neq(state);
+    }
 }
 
 // Animation initialization.
@@ -23,6 +37,8 @@ fn init(mut commands: Commands, asset_server: Res<AssetServer>) {
             ..default()
         },
         Transform::from_xyz(70.0, -58.0, 5.0),
+        DespawnOnExit(CardState::Running),
+        InRoom(Room::Living),
         Chair,
         State::Off,
         Interactable {
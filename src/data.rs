@@ -0,0 +1,42 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+
+// Asset loader for data files written in RON.
+struct RonLoader<T> {
+    extensions: &'static [&'static str],
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Asset + DeserializeOwned> AssetLoader for RonLoader<T> {
+    type Asset = T;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<T, BevyError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        self.extensions
+    }
+}
+
+// Register a data asset loaded from RON files with the given extensions, e.g. "strings.ron".
+pub fn register<T: Asset + DeserializeOwned>(app: &mut App, extensions: &'static [&'static str]) {
+    app.init_asset::<T>().register_asset_loader(RonLoader::<T> {
+        extensions,
+        marker: PhantomData,
+    });
+}
@@ -0,0 +1,201 @@
+use bevy::prelude::*;
+
+use crate::flickering_light::{Flicker, FlickerAssets, FlickerPresets};
+
+// Panel for tweaking the flicker presets while the card runs, toggled with F3.
+// NOTE: changes aren't saved, copy the values into "lights/card.flicker.ron" to keep them.
+#[derive(Component)]
+struct DebugPanel;
+
+// Preset and setting picked in the panel.
+#[derive(Resource, Default)]
+struct Selection {
+    preset: usize,
+    setting: usize,
+}
+
+// A flicker setting that can be changed from the panel, values step by a size and don't go below a minimum.
+enum Setting<'a> {
+    Value(&'a mut f32, f32, f32),
+    Octaves(&'a mut u32),
+}
+
+// Names of the flicker settings, in the order of `setting`.
+const SETTINGS: [&str; 8] = [
+    "intensity_amplitude",
+    "intensity_frequency",
+    "intensity_min",
+    "intensity_octaves",
+    "color_frequency",
+    "color_octaves",
+    "color_seed_offset",
+    "color_temperature",
+];
+
+const TOGGLE_KEY: KeyCode = KeyCode::F3;
+const PREVIOUS_KEY: KeyCode = KeyCode::BracketLeft;
+const NEXT_KEY: KeyCode = KeyCode::BracketRight;
+const DECREASE_KEY: KeyCode = KeyCode::Minus;
+const INCREASE_KEY: KeyCode = KeyCode::Equal;
+
+const FONT_SIZE: f32 = 8.0;
+const BACKGROUND_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.8);
+
+// Add the debug systems.
+pub fn add_systems(app: &mut App) {
+    app.init_resource::<Selection>()
+        .add_systems(Startup, init)
+        .add_systems(Update, (handle_toggle, handle_keys, handle_panel).chain());
+}
+
+// Setting of a preset by its index in SETTINGS, with the step it changes by and its minimum.
+// NOTE: the frequencies and temperature stay at least a step above zero, a zero temperature divides the color noise
+// by zero.
+fn setting(flicker: &mut Flicker, index: usize) -> Setting<'_> {
+    match index {
+        0 => Setting::Value(&mut flicker.intensity_amplitude, 0.05, 0.0),
+        1 => Setting::Value(&mut flicker.intensity_frequency, 0.1, 0.1),
+        2 => Setting::Value(&mut flicker.intensity_min, 0.05, 0.0),
+        3 => Setting::Octaves(&mut flicker.intensity_octaves),
+        4 => Setting::Value(&mut flicker.color_frequency, 0.5, 0.5),
+        5 => Setting::Octaves(&mut flicker.color_octaves),
+        6 => Setting::Value(&mut flicker.color_seed_offset, 10.0, 0.0),
+        _ => Setting::Value(&mut flicker.color_temperature, 0.05, 0.05),
+    }
+}
+
+// Preset names in order, so presets keep their place in the panel.
+fn names(presets: &FlickerPresets) -> Vec<String> {
+    let mut names: Vec<String> = presets.0.keys().cloned().collect();
+    names.sort();
+    names
+}
+
+// Pick a setting and step its value.
+fn handle_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    assets: Res<FlickerAssets>,
+    mut presets: ResMut<Assets<FlickerPresets>>,
+    mut selection: ResMut<Selection>,
+    panel: Single<&Visibility, With<DebugPanel>>,
+) {
+    if *panel == Visibility::Hidden {
+        return;
+    }
+
+    // Only touch the presets when a key is pressed, getting them mutably marks them as changed.
+    let step: f32 = if keys.just_pressed(INCREASE_KEY) {
+        1.0
+    } else if keys.just_pressed(DECREASE_KEY) {
+        -1.0
+    } else {
+        0.0
+    };
+    let mut offset = 0_isize;
+    if keys.just_pressed(NEXT_KEY) {
+        offset += 1;
+    }
+    if keys.just_pressed(PREVIOUS_KEY) {
+        offset -= 1;
+    }
+    if step == 0.0 && offset == 0 {
+        return;
+    }
+
+    let Some(presets) = presets.get_mut(&assets.presets) else {
+        return;
+    };
+    let names = names(presets);
+    let count = names.len() * SETTINGS.len();
+    if count == 0 {
+        return;
+    }
+
+    // Settings run on from one preset into the next.
+    let index = (selection.preset * SETTINGS.len() + selection.setting).min(count - 1);
+    let index = (index as isize + offset).rem_euclid(count as isize) as usize;
+    selection.preset = index / SETTINGS.len();
+    selection.setting = index % SETTINGS.len();
+
+    if let Some(flicker) = presets.0.get_mut(&names[selection.preset]) {
+        match setting(flicker, selection.setting) {
+            Setting::Value(value, size, min) => *value = step.mul_add(size, *value).max(min),
+            Setting::Octaves(octaves) => *octaves = octaves.saturating_add_signed(step as i32).max(1),
+        }
+    }
+}
+
+// Show the presets and their settings, marking the selected one.
+fn handle_panel(
+    assets: Res<FlickerAssets>,
+    presets: Res<Assets<FlickerPresets>>,
+    selection: Res<Selection>,
+    panel: Single<(&Visibility, &mut Text), With<DebugPanel>>,
+) {
+    let (visibility, mut text) = panel.into_inner();
+    if *visibility == Visibility::Hidden || (!selection.is_changed() && !presets.is_changed()) {
+        return;
+    }
+
+    let Some(presets) = presets.get(&assets.presets) else {
+        return;
+    };
+
+    let mut lines = vec!["flicker presets, [ ] select, - = change".to_string()];
+    for (i, name) in names(presets).into_iter().enumerate() {
+        // Settings are read from a copy, they're only borrowed mutably to change them.
+        let mut flicker = presets.0[&name].clone();
+        lines.push(name);
+        for (j, label) in SETTINGS.iter().enumerate() {
+            let marker = if i == selection.preset && j == selection.setting {
+                ">"
+            } else {
+                " "
+            };
+            let value = match setting(&mut flicker, j) {
+                Setting::Value(value, ..) => format!("{value:.2}"),
+                Setting::Octaves(octaves) => octaves.to_string(),
+            };
+            lines.push(format!("{marker} {label}: {value}"));
+        }
+    }
+
+    text.0 = lines.join("\n");
+}
+
+// Show or hide the panel.
+fn handle_toggle(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut selection: ResMut<Selection>,
+    mut panel: Single<&mut Visibility, With<DebugPanel>>,
+) {
+    if keys.just_pressed(TOGGLE_KEY) {
+        **panel = if **panel == Visibility::Hidden {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        // Redraw the panel as it opens.
+        selection.set_changed();
+    }
+}
+
+// Debug panel initialization.
+// NOTE: the panel isn't part of the scene so it stays open when the card is reset.
+fn init(mut commands: Commands) {
+    commands.spawn((
+        DebugPanel,
+        Text::new(""),
+        TextFont::from_font_size(FONT_SIZE),
+        Node {
+            position_type: PositionType::Absolute,
+            top: px(4),
+            right: px(4),
+            padding: UiRect::all(px(4)),
+            ..default()
+        },
+        BackgroundColor(BACKGROUND_COLOR),
+        GlobalZIndex(30),
+        Visibility::Hidden,
+    ));
+}
@@ -0,0 +1,525 @@
+use bevy::{
+    ecs::system::SystemParam, input::touch::Touches, platform::collections::HashMap, prelude::*, ui::UiTransform,
+};
+use serde::Deserialize;
+
+use crate::{
+    accessibility::AccessibilitySettings,
+    app::CardState,
+    data,
+    input::Direction,
+    interaction::{Interactable, InteractionEvent},
+    localization::Localization,
+    menu::MenuState,
+    settings::Settings,
+    timeline::Actor,
+};
+
+// Whether a dialogue that needs answering is showing, player movement is blocked while it is.
+#[derive(States, Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum DialogueState {
+    #[default]
+    Closed,
+    Open,
+}
+
+// Dialogue tree authored as data, e.g. "dialogue/santa.dialogue.ron".
+#[derive(Asset, TypePath, Deserialize)]
+pub struct Dialogue {
+    // Blocking dialogues wait for the player, the others close by themselves and leave the player free to move.
+    #[serde(default = "blocking_default")]
+    pub blocking: bool,
+    #[serde(default)]
+    pub start: Option<String>,
+    pub nodes: HashMap<String, DialogueNode>,
+}
+
+// A line said by a speaker, an actor name or interactable id, with the text as a localization key.
+#[derive(Deserialize)]
+pub struct DialogueNode {
+    pub speaker: String,
+    pub text: String,
+    #[serde(default)]
+    pub choices: Vec<Choice>,
+    #[serde(default)]
+    pub next: Option<String>,
+    // Seconds to wait after the text is shown before moving on by itself.
+    #[serde(default)]
+    pub timeout: Option<f32>,
+}
+
+// An answer the player can pick, leading to another node or ending the dialogue.
+#[derive(Deserialize)]
+pub struct Choice {
+    pub text: String,
+    #[serde(default)]
+    pub next: Option<String>,
+}
+
+// Message sent to start a dialogue, at its start node unless another is given.
+#[derive(Message)]
+pub struct StartDialogueEvent {
+    pub dialogue: Handle<Dialogue>,
+    pub node: Option<String>,
+}
+
+// The dialogue being shown.
+struct Conversation {
+    dialogue: Handle<Dialogue>,
+    // `None` until the dialogue has loaded and the start node is picked.
+    node: Option<String>,
+    start: Option<String>,
+    // Characters typed out so far and the time since the whole line was shown.
+    typed: f32,
+    idle: f32,
+    selected: usize,
+    bubble: Option<Entity>,
+}
+
+// The conversation being shown, if any.
+#[derive(Resource, Default)]
+pub struct DialogueRunner(Option<Conversation>);
+
+#[derive(Clone, Resource)]
+struct DialogueAssets {
+    props: Handle<Dialogue>,
+}
+
+// Speech bubble anchored above the speaker.
+#[derive(Component)]
+struct Bubble;
+
+// Text inside the speech bubble.
+#[derive(Component)]
+struct BubbleText;
+
+// Answer button inside the speech bubble.
+#[derive(Component)]
+struct ChoiceButton(usize);
+
+// Input used to answer dialogues, the same bindings as moving the man.
+#[derive(SystemParam)]
+struct Controls<'w, 's> {
+    settings: Res<'w, Settings>,
+    keyboard: Res<'w, ButtonInput<KeyCode>>,
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+    touches: Res<'w, Touches>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+    buttons: Query<'w, 's, &'static Interaction, With<Button>>,
+}
+
+// Actors and interactables that can speak a line.
+type Speakers<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static GlobalTransform,
+        Option<&'static Actor>,
+        Option<&'static Interactable>,
+    ),
+    Or<(With<Actor>, With<Interactable>)>,
+>;
+
+const CHARS_PER_SECOND: f32 = 30.0;
+
+// Space between the top of the speaker and the bubble, in world units.
+const BUBBLE_GAP: f32 = 3.0;
+// Half the height of speakers that aren't interactables, like the man and santa.
+const ACTOR_HALF_HEIGHT: f32 = 16.0;
+
+const FONT_SIZE: f32 = 12.0;
+const BUBBLE_COLOR: Color = Color::srgb(0.96, 0.95, 0.9);
+const BORDER_COLOR: Color = Color::srgb(0.1, 0.1, 0.15);
+const TEXT_COLOR: Color = Color::srgb(0.1, 0.1, 0.15);
+const SELECTED_COLOR: Color = Color::srgb(0.8, 0.2, 0.2);
+
+fn blocking_default() -> bool {
+    true
+}
+
+impl Controls<'_, '_> {
+    // Direction pressed this frame on the keyboard or gamepad.
+    fn direction(&self) -> Option<Direction> {
+        let bindings = self.settings.bindings;
+        let gamepad = |button| self.gamepads.iter().any(|gamepad| gamepad.just_pressed(button));
+
+        if self.keyboard.just_pressed(bindings.left) || gamepad(GamepadButton::DPadLeft) {
+            Some(Direction::Left)
+        } else if self.keyboard.just_pressed(bindings.right) || gamepad(GamepadButton::DPadRight) {
+            Some(Direction::Right)
+        } else if self.keyboard.just_pressed(bindings.interact) || gamepad(GamepadButton::South) {
+            Some(Direction::Up)
+        } else {
+            None
+        }
+    }
+
+    // Whether the player clicked or tapped somewhere other than a button.
+    fn clicked(&self) -> bool {
+        let pressed = self.mouse.just_pressed(MouseButton::Left) || self.touches.any_just_pressed();
+        pressed && self.buttons.iter().all(|interaction| *interaction == Interaction::None)
+    }
+}
+
+impl DialogueRunner {
+    // Whether a conversation is waiting for its dialogue to load before it shows.
+    pub fn starting(&self) -> bool {
+        self.0.as_ref().is_some_and(|conversation| conversation.node.is_none())
+    }
+}
+
+// Add the dialogue systems.
+pub fn add_systems(app: &mut App) {
+    data::register::<Dialogue>(app, &["dialogue.ron"]);
+
+    app.init_state::<DialogueState>()
+        .init_resource::<DialogueRunner>()
+        .add_message::<StartDialogueEvent>()
+        .add_systems(OnEnter(CardState::Running), init)
+        .add_systems(
+            Update,
+            (
+                handle_flavor_text,
+                handle_start,
+                handle_controls.run_if(in_state(MenuState::Closed)),
+                handle_typing,
+                handle_bubbles,
+                handle_position,
+                handle_state,
+            )
+                .chain(),
+        );
+}
+
+// Spawn the speech bubble for the current line.
+fn handle_bubbles(
+    mut commands: Commands,
+    dialogues: Res<Assets<Dialogue>>,
+    localization: Res<Localization>,
+    mut runner: ResMut<DialogueRunner>,
+) {
+    let Some(conversation) = &mut runner.0 else {
+        return;
+    };
+    let (None, Some(node)) = (conversation.bubble, &conversation.node) else {
+        return;
+    };
+    let Some(line) = dialogues
+        .get(&conversation.dialogue)
+        .and_then(|dialogue| dialogue.nodes.get(node))
+    else {
+        return;
+    };
+
+    let bubble = commands
+        .spawn((
+            Bubble,
+            Node {
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                max_width: px(180),
+                padding: UiRect::axes(px(6), px(3)),
+                border: UiRect::all(px(2)),
+                row_gap: px(2),
+                ..default()
+            },
+            // Anchor the bubble by the middle of its bottom edge.
+            UiTransform::from_translation(Val2::percent(-50.0, -100.0)),
+            BackgroundColor(BUBBLE_COLOR),
+            BorderColor::all(BORDER_COLOR),
+            GlobalZIndex(5),
+            // Hidden until it's been positioned over the speaker.
+            Visibility::Hidden,
+            DespawnOnExit(CardState::Running),
+        ))
+        .with_child((
+            BubbleText,
+            Text::new(""),
+            TextFont::from_font_size(FONT_SIZE),
+            TextColor(TEXT_COLOR),
+        ))
+        .id();
+
+    for (index, choice) in line.choices.iter().enumerate() {
+        commands.entity(bubble).with_child((
+            Button,
+            ChoiceButton(index),
+            Text::new(format!("> {}", localization.get(&choice.text))),
+            TextFont::from_font_size(FONT_SIZE),
+            TextColor(TEXT_COLOR),
+            // Choices show once the line is typed out.
+            Visibility::Hidden,
+        ));
+    }
+
+    conversation.bubble = Some(bubble);
+}
+
+// Type out the current line and show the choices once it's done.
+// NOTE: reduced motion shows the whole line at once.
+fn handle_typing(
+    time: Res<Time>,
+    accessibility: Res<AccessibilitySettings>,
+    dialogues: Res<Assets<Dialogue>>,
+    localization: Res<Localization>,
+    mut runner: ResMut<DialogueRunner>,
+    mut texts: Query<&mut Text, With<BubbleText>>,
+    mut choices: Query<(&ChoiceButton, &mut Visibility, &mut TextColor)>,
+) {
+    let Some(conversation) = &mut runner.0 else {
+        return;
+    };
+    let Some(line) = conversation
+        .node
+        .as_ref()
+        .and_then(|node| dialogues.get(&conversation.dialogue)?.nodes.get(node))
+    else {
+        return;
+    };
+
+    let text = localization.get(&line.text);
+    let length = text.chars().count() as f32;
+    if accessibility.reduced_motion {
+        conversation.typed = length;
+    } else {
+        conversation.typed = (conversation.typed + CHARS_PER_SECOND * time.delta_secs()).min(length);
+    }
+    if conversation.typed >= length {
+        conversation.idle += time.delta_secs();
+    }
+
+    for mut bubble_text in &mut texts {
+        let typed: String = text.chars().take(conversation.typed as usize).collect();
+        if bubble_text.0 != typed {
+            bubble_text.0 = typed;
+        }
+    }
+
+    for (choice, mut visibility, mut color) in &mut choices {
+        visibility.set_if_neq(if conversation.typed >= length {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+        color.set_if_neq(TextColor(if choice.0 == conversation.selected {
+            SELECTED_COLOR
+        } else {
+            TEXT_COLOR
+        }));
+    }
+}
+
+// Move through the dialogue with the movement keys, gamepad or pointer, and close lines that time out.
+fn handle_controls(
+    mut commands: Commands,
+    controls: Controls,
+    dialogues: Res<Assets<Dialogue>>,
+    localization: Res<Localization>,
+    mut runner: ResMut<DialogueRunner>,
+    choice_buttons: Query<(&Interaction, &ChoiceButton), Changed<Interaction>>,
+) {
+    let Some(conversation) = &mut runner.0 else {
+        return;
+    };
+    let Some(dialogue) = dialogues.get(&conversation.dialogue) else {
+        return;
+    };
+    let Some(line) = conversation.node.as_ref().and_then(|node| dialogue.nodes.get(node)) else {
+        return;
+    };
+
+    let typed_out = conversation.typed >= localization.get(&line.text).chars().count() as f32;
+    let timed_out = line.choices.is_empty() && line.timeout.is_some_and(|timeout| conversation.idle >= timeout);
+
+    // Only blocking dialogues take input, the others are left alone for the player to walk away from.
+    let (direction, clicked) = if dialogue.blocking {
+        (controls.direction(), controls.clicked())
+    } else {
+        (None, false)
+    };
+    let picked = choice_buttons
+        .iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, choice)| choice.0);
+
+    let confirm = direction == Some(Direction::Up) || clicked;
+    let next = if timed_out {
+        Some(line.next.clone())
+    } else if !typed_out {
+        // Show the whole line before it can be answered.
+        if confirm {
+            conversation.typed = f32::MAX;
+        }
+        None
+    } else if let Some(index) = picked {
+        Some(line.choices[index].next.clone())
+    } else if line.choices.is_empty() {
+        confirm.then(|| line.next.clone())
+    } else {
+        match direction {
+            Some(Direction::Left) => {
+                conversation.selected = conversation.selected.saturating_sub(1);
+                None
+            }
+            Some(Direction::Right) => {
+                conversation.selected = (conversation.selected + 1).min(line.choices.len() - 1);
+                None
+            }
+            _ => confirm.then(|| line.choices[conversation.selected].next.clone()),
+        }
+    };
+
+    let Some(next) = next else {
+        return;
+    };
+    if let Some(bubble) = conversation.bubble.take() {
+        commands.entity(bubble).despawn();
+    }
+    match next {
+        Some(node) => {
+            conversation.node = Some(node);
+            conversation.typed = 0.0;
+            conversation.idle = 0.0;
+            conversation.selected = 0;
+        }
+        None => runner.0 = None,
+    }
+}
+
+// Show a prop's flavor text when the man interacts with it.
+fn handle_flavor_text(
+    assets: Res<DialogueAssets>,
+    dialogues: Res<Assets<Dialogue>>,
+    mut events: MessageReader<InteractionEvent>,
+    mut dialogue_events: MessageWriter<StartDialogueEvent>,
+) {
+    let Some(props) = dialogues.get(&assets.props) else {
+        return;
+    };
+
+    for event in events.read() {
+        if props.nodes.contains_key(&event.id) {
+            dialogue_events.write(StartDialogueEvent {
+                dialogue: assets.props.clone(),
+                node: Some(event.id.clone()),
+            });
+        }
+    }
+}
+
+// Keep the speech bubble above the speaker.
+fn handle_position(
+    dialogues: Res<Assets<Dialogue>>,
+    runner: Res<DialogueRunner>,
+    ui_scale: Res<UiScale>,
+    camera: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+    speakers: Speakers,
+    mut bubbles: Query<(&mut Node, &mut Visibility), With<Bubble>>,
+) {
+    let Some(conversation) = &runner.0 else {
+        return;
+    };
+    let Some(line) = conversation
+        .node
+        .as_ref()
+        .and_then(|node| dialogues.get(&conversation.dialogue)?.nodes.get(node))
+    else {
+        return;
+    };
+
+    let speaker = speakers.iter().find(|(_, actor, interactable)| {
+        actor.is_some_and(|actor| actor.0 == line.speaker)
+            || interactable.is_some_and(|interactable| interactable.id == line.speaker)
+    });
+    let Some((transform, _, interactable)) = speaker else {
+        return;
+    };
+
+    let half_height = interactable.map_or(ACTOR_HALF_HEIGHT, |interactable| interactable.sprite_height / 2.0);
+    let anchor = transform.translation() + Vec3::Y * (half_height + BUBBLE_GAP);
+    let (camera, camera_transform) = *camera;
+    let Ok(position) = camera.world_to_viewport(camera_transform, anchor) else {
+        return;
+    };
+    // The UI is laid out inside the letterboxed viewport, not the whole window.
+    let position = position - camera.logical_viewport_rect().map_or(Vec2::ZERO, |rect| rect.min);
+
+    for (mut node, mut visibility) in &mut bubbles {
+        node.left = px(position.x / ui_scale.0);
+        node.top = px(position.y / ui_scale.0);
+        visibility.set_if_neq(Visibility::Inherited);
+    }
+}
+
+// Start new dialogues, a blocking dialogue isn't interrupted by flavor text.
+fn handle_start(
+    mut commands: Commands,
+    dialogues: Res<Assets<Dialogue>>,
+    mut events: MessageReader<StartDialogueEvent>,
+    mut runner: ResMut<DialogueRunner>,
+) {
+    for event in events.read() {
+        let blocking = |handle: &Handle<Dialogue>| dialogues.get(handle).is_none_or(|dialogue| dialogue.blocking);
+        if let Some(conversation) = &runner.0
+            && blocking(&conversation.dialogue)
+            && !blocking(&event.dialogue)
+        {
+            continue;
+        }
+
+        if let Some(bubble) = runner.0.take().and_then(|conversation| conversation.bubble) {
+            commands.entity(bubble).despawn();
+        }
+        runner.0 = Some(Conversation {
+            dialogue: event.dialogue.clone(),
+            node: None,
+            start: event.node.clone(),
+            typed: 0.0,
+            idle: 0.0,
+            selected: 0,
+            bubble: None,
+        });
+    }
+
+    // Pick the start node once the dialogue has loaded.
+    if let Some(conversation) = &mut runner.0
+        && conversation.node.is_none()
+        && let Some(dialogue) = dialogues.get(&conversation.dialogue)
+    {
+        match conversation.start.clone().or_else(|| dialogue.start.clone()) {
+            Some(node) => conversation.node = Some(node),
+            None => runner.0 = None,
+        }
+    }
+}
+
+// Keep the dialogue state in step with the blocking dialogue being shown.
+fn handle_state(
+    dialogues: Res<Assets<Dialogue>>,
+    runner: Res<DialogueRunner>,
+    state: Res<State<DialogueState>>,
+    mut next_state: ResMut<NextState<DialogueState>>,
+) {
+    let open = runner
+        .0
+        .as_ref()
+        .and_then(|conversation| dialogues.get(&conversation.dialogue))
+        .is_some_and(|dialogue| dialogue.blocking);
+
+    let dialogue_state = if open {
+        DialogueState::Open
+    } else {
+        DialogueState::Closed
+    };
+    if *state.get() != dialogue_state {
+        next_state.set(dialogue_state);
+    }
+}
+
+// Load the prop flavor text and clear any dialogue left from before a reset.
+fn init(mut commands: Commands, asset_server: Res<AssetServer>, mut runner: ResMut<DialogueRunner>) {
+    commands.insert_resource(DialogueAssets {
+        props: asset_server.load("dialogue/props.dialogue.ron"),
+    });
+    runner.0 = None;
+}
@@ -4,8 +4,11 @@ use rand::Rng;
 
 use crate::{
     animation::AnimationConfig,
-    flickering_light::FlickeringLight,
-    interaction::{Interactable, InteractionEvent, State},
+    app::CardState,
+    flickering_light::{FlickeringLight, LightFlickerSet, LightInsertionSet},
+    interaction::{Interactable, InteractionEvent, State, StateSet},
+    particles::{Emitter, Shape},
+    rooms::{InRoom, Room},
 };
 
 #[derive(Clone, Resource)]
@@ -15,8 +18,14 @@ struct SpriteAssets {
     off_sprite: Handle<Image>,
 }
 
-#[derive(Component)]
-struct Fireplace;
+// Fire burning through its fuel, the heat rises as it catches and falls as the fuel runs low.
+#[derive(Component, Default)]
+pub struct Fireplace {
+    // Seconds of burning at full heat left in the logs.
+    fuel: f32,
+    // How strongly the fire is burning in the range [0, 1].
+    heat: f32,
+}
 
 const INTERACTABLE_ID: &str = "fireplace";
 
@@ -27,29 +36,69 @@ const LIGHT_COLORS: [Color; 3] = [
     Color::srgb(1.0, 0.58, 0.22),
 ];
 
+// Seconds of fuel when the fire is lit and for each log added, with a limit to how many logs fit.
+const START_FUEL: f32 = 150.0;
+const LOG_FUEL: f32 = 60.0;
+const MAX_FUEL: f32 = 300.0;
+
+// Fuel the fire burns at full heat with, it dies down as the fuel drops below it.
+const FULL_FUEL: f32 = 90.0;
+
+// How fast the heat can rise as the fire catches and fall as it dies down, per second.
+const CATCH_RATE: f32 = 0.15;
+const COOL_RATE: f32 = 0.05;
+
+// Heat below which the fire goes out once there's no fuel to keep it going.
+const EMBER_HEAT: f32 = 0.05;
+
+// Animation frame rate from a dying fire to a roaring one.
+const MIN_FPS: f32 = 3.0;
+const MAX_FPS: f32 = 8.0;
+
+const FIRE_VOLUME: f32 = 0.75;
+
+// Embers rising out of the firebox and smoke from the chimney top, just off the top of the card, that the wind blows
+// into view. Offsets are from the fireplace.
+const EMBERS_OFFSET: Vec3 = Vec3::new(0.0, -22.0, 0.5);
+const EMBERS_COLORS: [Color; 2] = [Color::srgb(1.0, 0.7, 0.2), Color::srgba(0.8, 0.1, 0.0, 0.0)];
+const SMOKE_OFFSET: Vec3 = Vec3::new(0.0, 104.0, -2.5);
+const SMOKE_COLORS: [Color; 2] = [Color::srgba(0.6, 0.6, 0.65, 0.5), Color::srgba(0.5, 0.5, 0.55, 0.0)];
+
+impl Fireplace {
+    // How strongly the fire is burning in the range [0, 1], 0 when it's out.
+    pub const fn heat(&self) -> f32 {
+        self.heat
+    }
+}
+
 // Add the animation systems.
 pub fn add_systems(app: &mut App) {
-    app.add_systems(Startup, init).add_systems(
+    app.add_systems(OnEnter(CardState::Running), init).add_systems(
         Update,
         (
             handle_animations,
-            handle_interaction,
-            handle_sound,
-            handle_light.in_set(crate::flickering_light::LightInsertionSet),
+            (handle_interaction, handle_burn).in_set(StateSet::Change),
+            (handle_fuel, handle_sprite, handle_sound).in_set(StateSet::Effects),
+            handle_light.in_set(StateSet::Effects).in_set(LightInsertionSet),
+            handle_light_heat.after(LightFlickerSet),
+            handle_emitters,
         ),
     );
 }
 
 // Manage the animation frame timing.
-fn handle_animations(time: Res<Time>, mut query: Query<(&mut AnimationConfig, &mut Sprite, &State), With<Fireplace>>) {
+fn handle_animations(time: Res<Time>, mut query: Query<(&mut AnimationConfig, &mut Sprite, &State, &Fireplace)>) {
     let mut rng = rand::rng();
 
-    for (mut config, mut sprite, state) in &mut query {
+    for (mut config, mut sprite, state, fireplace) in &mut query {
         // Off state only has one frame so skip.
         if *state == State::Off {
             continue;
         }
 
+        // The flames dance faster the hotter the fire burns.
+        config.fps = fireplace.heat.mul_add(MAX_FPS - MIN_FPS, MIN_FPS) as u8;
+
         // Track how long the current sprite has been displayed.
         config.frame_timer.tick(time.delta());
 
@@ -67,39 +116,82 @@ fn handle_animations(time: Res<Time>, mut query: Query<(&mut AnimationConfig, &m
     }
 }
 
-// Listen for interaction events and update the state.
-fn handle_interaction(
-    sprite_assets: Res<SpriteAssets>,
-    mut events: MessageReader<InteractionEvent>,
-    mut query: Query<(&mut State, &mut Sprite), With<Fireplace>>,
-) {
+// Listen for interaction events, lighting the fire or adding a log to keep it going.
+fn handle_interaction(mut events: MessageReader<InteractionEvent>, mut query: Query<(&mut State, &mut Fireplace)>) {
     for event in events.read() {
         if event.id == INTERACTABLE_ID
-            && let Ok((mut state, mut sprite)) = query.single_mut()
+            && let Ok((mut state, mut fireplace)) = query.single_mut()
         {
             match *state {
-                State::Off => {
-                    *state = State::On;
-                    sprite.image = sprite_assets.running_sprite.clone();
-                    sprite.texture_atlas = Some(TextureAtlas {
-                        layout: sprite_assets.running_layout.clone(),
-                        index: 0,
-                    });
-                }
-
-                State::On => {
-                    *state = State::Off;
-                    sprite.image = sprite_assets.off_sprite.clone();
-                    sprite.texture_atlas = None;
-                }
+                State::Off => *state = State::On,
+                State::On => fireplace.fuel = (fireplace.fuel + LOG_FUEL).min(MAX_FUEL),
+            }
+        }
+    }
+}
+
+// Burn through the fuel, the fire goes out once it's down to embers with nothing left to burn.
+fn handle_burn(time: Res<Time>, mut query: Query<(&mut State, &mut Fireplace)>) {
+    for (mut state, mut fireplace) in &mut query {
+        // A fire lit this frame doesn't have its fuel laid yet.
+        if *state == State::Off || state.is_changed() {
+            continue;
+        }
+
+        let target = (fireplace.fuel / FULL_FUEL).min(1.0);
+        let delta = time.delta_secs();
+        fireplace.heat += (target - fireplace.heat).clamp(-COOL_RATE * delta, CATCH_RATE * delta);
+        fireplace.fuel = fireplace.heat.mul_add(-delta, fireplace.fuel).max(0.0);
+
+        if target < EMBER_HEAT && fireplace.heat < EMBER_HEAT {
+            *state = State::Off;
+        }
+    }
+}
+
+// Lay the fuel for a fire as it's lit and clear out the fireplace as it goes out.
+// NOTE: this also covers the fire being lit or put out by a timeline.
+fn handle_fuel(mut query: Query<(&State, &mut Fireplace), Changed<State>>) {
+    for (state, mut fireplace) in &mut query {
+        match *state {
+            State::On => fireplace.fuel = fireplace.fuel.max(START_FUEL),
+            State::Off => *fireplace = Fireplace::default(),
+        }
+    }
+}
+
+// Swap between the running and off sprites when the state changes.
+fn handle_sprite(
+    sprite_assets: Res<SpriteAssets>,
+    mut query: Query<(&State, &mut Sprite), (With<Fireplace>, Changed<State>)>,
+) {
+    for (state, mut sprite) in &mut query {
+        match *state {
+            State::On => {
+                sprite.image = sprite_assets.running_sprite.clone();
+                sprite.texture_atlas = Some(TextureAtlas {
+                    layout: sprite_assets.running_layout.clone(),
+                    index: 0,
+                });
+            }
+
+            State::Off => {
+                sprite.image = sprite_assets.off_sprite.clone();
+                sprite.texture_atlas = None;
             }
         }
     }
 }
 
-// Control audio playback based on fireplace state
-fn handle_sound(query: Query<(&State, &mut SpatialAudioSink), (With<Fireplace>, Changed<State>)>) {
-    for (state, audio_sink) in &query {
+// Control audio playback based on fireplace state, the fire gets louder the hotter it burns.
+// NOTE: setting the volume every frame overrides the settings, so the global volume is applied here too.
+fn handle_sound(global_volume: Res<GlobalVolume>, mut query: Query<(Ref<State>, &Fireplace, &mut SpatialAudioSink)>) {
+    for (state, fireplace, mut audio_sink) in &mut query {
+        audio_sink.set_volume(Volume::Linear(FIRE_VOLUME * fireplace.heat) * global_volume.volume);
+        if !state.is_changed() {
+            continue;
+        }
+
         match *state {
             // Start the fireplace sound effect if it isn't already running.
             State::On => {
@@ -119,24 +211,12 @@ fn handle_light(
     mut commands: Commands,
     mut query: Query<(Entity, &State, &mut PointLight2d), (With<Fireplace>, Changed<State>)>,
 ) {
-    let mut rng = rand::rng();
-
     for (entity, state, mut light) in &mut query {
         match *state {
             State::On => {
-                commands.entity(entity).insert(FlickeringLight {
-                    seed: rng.random_range(0.0..1000.0),
-                    intensity_amplitude: 0.4,
-                    intensity_frequency: 2.0,
-                    intensity_min: 0.6,
-                    intensity_octaves: 4,
-                    color_frequency: 1.0,
-                    color_octaves: 2,
-                    color_seed_offset: 100.0,
-                    color_temperature: 0.2,
-                    colors: LIGHT_COLORS.to_vec(),
-                    time_offset: rng.random_range(0.0..100.0),
-                });
+                commands
+                    .entity(entity)
+                    .insert(FlickeringLight::new("fire", &LIGHT_COLORS));
             }
             State::Off => {
                 commands.entity(entity).remove::<FlickeringLight>();
@@ -146,6 +226,24 @@ fn handle_light(
     }
 }
 
+// Send up more embers and smoke the hotter the fire burns.
+fn handle_emitters(fireplaces: Query<(&Fireplace, &Children)>, mut emitters: Query<&mut Emitter>) {
+    for (fireplace, children) in &fireplaces {
+        for child in children {
+            if let Ok(mut emitter) = emitters.get_mut(*child) {
+                emitter.strength = fireplace.heat;
+            }
+        }
+    }
+}
+
+// Dim the flickering light to the heat of the fire.
+fn handle_light_heat(mut query: Query<(&mut PointLight2d, &Fireplace), With<FlickeringLight>>) {
+    for (mut light, fireplace) in &mut query {
+        light.intensity *= fireplace.heat;
+    }
+}
+
 // Animation initialization.
 fn init(
     mut commands: Commands,
@@ -168,13 +266,15 @@ fn init(
             ..default()
         },
         Transform::from_translation(Vec3::new(116.0, -31.0, 5.0)),
-        Fireplace,
-        AnimationConfig::new(0, 4, 6),
+        DespawnOnExit(CardState::Running),
+        InRoom(Room::Living),
+        Fireplace::default(),
+        AnimationConfig::new(0, 4, MIN_FPS as u8),
         State::Off,
         AudioPlayer::new(asset_server.load("fireplace/fire.ogg")),
         PlaybackSettings::LOOP
             .with_spatial(true)
-            .with_volume(Volume::Linear(0.75))
+            .with_volume(Volume::Linear(0.0))
             .paused(),
         Interactable {
             id: INTERACTABLE_ID.to_string(),
@@ -191,5 +291,31 @@ fn init(
             cast_shadows: true,
             ..default()
         },
+        children![
+            (
+                Transform::from_translation(EMBERS_OFFSET),
+                Emitter::new(
+                    6.0,
+                    Shape::Box(Vec2::new(10.0, 2.0)),
+                    (1.0, 2.5),
+                    (Vec2::new(-4.0, 8.0), Vec2::new(4.0, 18.0)),
+                )
+                .with_gravity(-4.0)
+                .with_color(EMBERS_COLORS[0], EMBERS_COLORS[1]),
+            ),
+            (
+                Transform::from_translation(SMOKE_OFFSET),
+                Emitter::new(
+                    4.0,
+                    Shape::Box(Vec2::new(2.0, 1.0)),
+                    (4.0, 7.0),
+                    (Vec2::new(-1.0, 2.0), Vec2::new(1.0, 5.0)),
+                )
+                .with_gravity(-1.0)
+                .with_wind(1.0)
+                .with_size(3.0, 10.0)
+                .with_color(SMOKE_COLORS[0], SMOKE_COLORS[1]),
+            ),
+        ],
     ));
 }
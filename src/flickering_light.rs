@@ -1,11 +1,25 @@
-use bevy::prelude::*;
+use bevy::{platform::collections::HashMap, prelude::*};
 use bevy_light_2d::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
 
-use crate::noise;
+use crate::{
+    accessibility::{AccessibilitySettings, REDUCED_FLICKER_AMPLITUDE, REDUCED_FLICKER_SPEED},
+    data,
+    noise::{Fractal, Noise},
+};
 
+// Light flickering through its colors with a named preset.
 #[derive(Component)]
 pub struct FlickeringLight {
-    pub seed: f32,
+    preset: String,
+    colors: Vec<Color>,
+    noise: Noise,
+}
+
+// How a light flickers, authored by name in "lights/card.flicker.ron".
+#[derive(Clone, Debug, Deserialize)]
+pub struct Flicker {
     pub intensity_amplitude: f32,
     pub intensity_frequency: f32,
     pub intensity_min: f32,
@@ -14,16 +28,46 @@ pub struct FlickeringLight {
     pub color_octaves: u32,
     pub color_seed_offset: f32,
     pub color_temperature: f32,
-    pub colors: Vec<Color>,
-    pub time_offset: f32,
+}
+
+// Flicker presets by name.
+#[derive(Asset, TypePath, Deserialize)]
+#[serde(transparent)]
+pub struct FlickerPresets(pub HashMap<String, Flicker>);
+
+#[derive(Resource)]
+pub struct FlickerAssets {
+    pub presets: Handle<FlickerPresets>,
 }
 
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct LightInsertionSet;
 
+// Flickering is done for the frame, anything changing the lights further runs after it.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LightFlickerSet;
+
+impl FlickeringLight {
+    // Flicker with the preset through the colors, with randomly seeded noise so lights with the same preset aren't in
+    // step.
+    pub fn new(preset: &str, colors: &[Color]) -> Self {
+        Self {
+            preset: preset.to_string(),
+            colors: colors.to_vec(),
+            noise: Noise::new(rand::rng().random()),
+        }
+    }
+}
+
 // Add the animation systems.
+// NOTE: presets are looked up every frame so edits from hot reloading or the debug panel show up right away.
 pub fn add_systems(app: &mut App) {
-    app.add_systems(Update, handle_light_flicker.after(LightInsertionSet));
+    data::register::<FlickerPresets>(app, &["flicker.ron"]);
+
+    app.add_systems(Startup, init).add_systems(
+        Update,
+        handle_light_flicker.in_set(LightFlickerSet).after(LightInsertionSet),
+    );
 }
 
 // Blend the colors using weights.
@@ -42,27 +86,42 @@ fn blend_colors(colors: &Vec<Color>, weights: &Vec<f32>) -> Color {
     Color::srgb(r, g, b)
 }
 
-// Apply noise-based flicker to the light color and intensity.
-fn handle_light_flicker(time: Res<Time>, mut query: Query<(&mut PointLight2d, &FlickeringLight)>) {
+// Apply noise-based flicker to the light color and intensity, slowed and softened with reduced motion.
+fn handle_light_flicker(
+    time: Res<Time>,
+    accessibility: Res<AccessibilitySettings>,
+    assets: Res<FlickerAssets>,
+    presets: Res<Assets<FlickerPresets>>,
+    mut query: Query<(&mut PointLight2d, &FlickeringLight)>,
+) {
+    let Some(presets) = presets.get(&assets.presets) else {
+        return;
+    };
+
+    let (speed, amplitude) = if accessibility.reduced_motion {
+        (REDUCED_FLICKER_SPEED, REDUCED_FLICKER_AMPLITUDE)
+    } else {
+        (1.0, 1.0)
+    };
+
     for (mut light, params) in &mut query {
-        let time = time.elapsed_secs() + params.time_offset;
+        let Some(flicker) = presets.0.get(&params.preset) else {
+            continue;
+        };
+        let time = time.elapsed_secs() * speed;
 
         // Intensity randomization.
-        let intensity_noise = noise::generate(time * params.intensity_frequency, params.seed, params.intensity_octaves);
-        light.intensity = intensity_noise.mul_add(params.intensity_amplitude, params.intensity_min);
+        let fractal = Fractal {
+            octaves: flicker.intensity_octaves,
+            ..default()
+        };
+        let intensity_noise = params.noise.fractal_1d(&fractal, time * flicker.intensity_frequency);
+        light.intensity = intensity_noise.mul_add(flicker.intensity_amplitude * amplitude, flicker.intensity_min);
 
         // Color randomization.
         light.color = blend_colors(
             &params.colors,
-            &weights(
-                time,
-                params.seed,
-                params.color_frequency,
-                params.colors.len(),
-                params.color_octaves,
-                params.color_seed_offset,
-                params.color_temperature,
-            ),
+            &weights(time, &params.noise, flicker, params.colors.len()),
         );
     }
 }
@@ -77,23 +136,26 @@ fn softmax(logits: &[f32], temperature: f32) -> Vec<f32> {
     exp_values.iter().map(|x| x / sum).collect()
 }
 
-// Generate weights using softmax normalization of noise-generated logits.
-fn weights(
-    time: f32,
-    seed: f32,
-    frequency: f32,
-    number: usize,
-    octaves: u32,
-    seed_offset: f32,
-    temperature: f32,
-) -> Vec<f32> {
+// Generate weights using softmax normalization of noise-generated logits, each color's noise a row apart.
+fn weights(time: f32, noise: &Noise, flicker: &Flicker, number: usize) -> Vec<f32> {
     let mut logits = Vec::with_capacity(number);
+    let fractal = Fractal {
+        octaves: flicker.color_octaves,
+        ..default()
+    };
 
     for i in 0..number {
-        let color_seed = (i as f32).mul_add(seed_offset, seed);
-        let noise_value = noise::generate(time * frequency, color_seed, octaves);
+        let row = i as f32 * flicker.color_seed_offset;
+        let noise_value = noise.fractal_2d(&fractal, time * flicker.color_frequency, row);
         logits.push(noise_value);
     }
 
-    softmax(&logits, temperature)
+    softmax(&logits, flicker.color_temperature)
+}
+
+// Load the flicker presets.
+fn init(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(FlickerAssets {
+        presets: asset_server.load("lights/card.flicker.ron"),
+    });
 }
@@ -1,10 +1,13 @@
 use bevy::{audio::Volume, prelude::*};
 use bevy_light_2d::prelude::*;
-use rand::Rng;
 
 use crate::{
-    flickering_light::FlickeringLight,
-    interaction::{InRange, Interactable, InteractionEvent, State},
+    app::CardState,
+    flickering_light::{FlickeringLight, LightInsertionSet},
+    interaction::{Interactable, InteractionEvent, State, StateSet},
+    rooms::{InRoom, Room},
+    sequencer::{SequencedLight, Sequencer},
+    wiring::{Circuit, Outlet, Schedule, Switch, Wired, WiringSet},
 };
 
 #[derive(Clone, Resource)]
@@ -22,26 +25,26 @@ struct SpriteAssets {
     xmas_light_green: Handle<Image>,
 }
 
+// Switch that plays the next christmas light pattern when pressed while it's on.
 #[derive(Component)]
-struct Switch;
+struct PatternSwitch;
 
+// Light that flickers with the preset through its colors while its circuit has power.
 #[derive(Component)]
-struct AtticLight;
-
-#[derive(Clone, Copy)]
-enum XmasLightColor {
-    Red,
-    Yellow,
-    Green,
+struct HouseLight {
+    preset: &'static str,
+    colors: [Color; 3],
 }
 
-#[derive(Component)]
-struct XmasLight(XmasLightColor);
-
-const INTERACTABLE_ID: &str = "light-switch";
+const LIGHT_SWITCH_ID: &str = "light-switch";
+const ATTIC_SWITCH_ID: &str = "attic-switch";
 
 const SWITCH_VOLUME: f32 = 0.40;
 
+// Seconds the porch light's timer outlet is off and then on for.
+const PORCH_TIMER_OFF: f32 = 20.0;
+const PORCH_TIMER_ON: f32 = 60.0;
+
 // Light effect colors.
 const ATTIC_LIGHT_COLORS: [Color; 3] = [
     Color::srgb(1.0, 0.6, 0.2),
@@ -49,6 +52,12 @@ const ATTIC_LIGHT_COLORS: [Color; 3] = [
     Color::srgb(1.0, 0.5, 0.3),
 ];
 
+const PORCH_LIGHT_COLORS: [Color; 3] = [
+    Color::srgb(1.0, 0.85, 0.6),
+    Color::srgb(1.0, 0.8, 0.55),
+    Color::srgb(0.95, 0.85, 0.65),
+];
+
 const XMAS_LIGHT_RED_COLORS: [Color; 3] = [
     Color::srgb(1.0, 0.1, 0.1),
     Color::srgb(0.95, 0.05, 0.05),
@@ -69,27 +78,35 @@ const XMAS_LIGHT_GREEN_COLORS: [Color; 3] = [
 
 // Add the animation systems.
 pub fn add_systems(app: &mut App) {
-    app.add_systems(Startup, init).add_systems(
+    app.add_systems(OnEnter(CardState::Running), init).add_systems(
         Update,
         (
-            handle_interaction,
-            handle_light.in_set(crate::flickering_light::LightInsertionSet),
+            handle_interaction.in_set(StateSet::Change),
+            handle_switches.in_set(StateSet::Effects),
+            handle_light.after(WiringSet).in_set(LightInsertionSet),
         ),
     );
 }
 
-// Listen for interaction events and update the state.
-fn handle_interaction(mut events: MessageReader<InteractionEvent>, mut query: Query<&mut State, With<Switch>>) {
+// Listen for interaction events and flip the switches, pressing the pattern switch while the lights are on plays the
+// next pattern until there are none left.
+fn handle_interaction(
+    mut events: MessageReader<InteractionEvent>,
+    mut sequencer: Sequencer,
+    mut switches: Query<(&Interactable, &mut State, Has<PatternSwitch>), With<Switch>>,
+) {
     for event in events.read() {
-        if event.id == INTERACTABLE_ID
-            && let Ok(mut state) = query.single_mut()
-        {
-            match *state {
-                State::Off => {
-                    *state = State::On;
-                }
-
-                State::On => {
+        let Some((_, mut state, patterns)) = switches
+            .iter_mut()
+            .find(|(interactable, ..)| interactable.id == event.id)
+        else {
+            continue;
+        };
+
+        match *state {
+            State::Off => *state = State::On,
+            State::On => {
+                if !patterns || !sequencer.advance() {
                     *state = State::Off;
                 }
             }
@@ -97,124 +114,56 @@ fn handle_interaction(mut events: MessageReader<InteractionEvent>, mut query: Qu
     }
 }
 
-// Add or remove flickering light based on the fireplace state.
+// Add or remove the flickering light when its circuit gains or loses power.
 fn handle_light(
+    mut commands: Commands,
+    mut lights: Query<(Entity, &HouseLight, &Wired, &mut PointLight2d), Changed<Wired>>,
+) {
+    for (entity, house_light, wired, mut light) in &mut lights {
+        if wired.powered {
+            commands
+                .entity(entity)
+                .insert(FlickeringLight::new(house_light.preset, &house_light.colors));
+        } else {
+            commands.entity(entity).remove::<FlickeringLight>();
+            light.intensity = 0.0;
+        }
+    }
+}
+
+// Flip the switch sprite and play its click, turning the pattern switch on starts the patterns over.
+// NOTE: switches can be flipped from anywhere, e.g. by a timeline, so this doesn't depend on the man being in range.
+fn handle_switches(
     mut commands: Commands,
     audio_assets: Res<AudioAssets>,
     sprite_assets: Res<SpriteAssets>,
-    parent_query: Query<(&Children, &State, &mut Sprite), (With<Switch>, With<InRange>, Changed<State>)>,
-    mut light_query: Query<(Entity, &mut PointLight2d, Option<&AtticLight>, Option<&XmasLight>)>,
+    mut sequencer: Sequencer,
+    switches: Query<(Ref<State>, &mut Sprite, Has<PatternSwitch>), (With<Switch>, Changed<State>)>,
 ) {
-    let mut rng = rand::rng();
-
-    // Find the child light entity.
-    for (children, state, mut sprite) in parent_query {
-        for child in children.iter() {
-            if let Ok((entity, mut light, attic_light, xmas_light)) = light_query.get_mut(child) {
-                match *state {
-                    State::On => {
-                        sprite.image = sprite_assets.switch_on.clone();
-
-                        commands.spawn((
-                            AudioPlayer::new(audio_assets.on.clone()),
-                            PlaybackSettings::DESPAWN.with_volume(Volume::Linear(SWITCH_VOLUME)),
-                        ));
-
-                        if attic_light.is_some() {
-                            let colors = ATTIC_LIGHT_COLORS.to_vec();
-
-                            commands.entity(entity).insert(FlickeringLight {
-                                seed: rng.random_range(0.0..1000.0),
-                                intensity_amplitude: 0.2,
-                                intensity_frequency: 2.0,
-                                intensity_min: 0.3,
-                                intensity_octaves: 4,
-                                color_frequency: 100.0,
-                                color_octaves: 5,
-                                color_seed_offset: 100.0,
-                                color_temperature: 0.5,
-                                colors,
-                                time_offset: rng.random_range(0.0..100.0),
-                            });
-                        }
-
-                        if let Some(XmasLight(color)) = xmas_light {
-                            match color {
-                                XmasLightColor::Red => {
-                                    let colors = XMAS_LIGHT_RED_COLORS.to_vec();
-
-                                    commands.entity(entity).insert(FlickeringLight {
-                                        seed: rng.random_range(0.0..1000.0),
-                                        intensity_amplitude: 0.1,
-                                        intensity_frequency: 2.0,
-                                        intensity_min: 0.15,
-                                        intensity_octaves: 2,
-                                        color_frequency: 10.0,
-                                        color_octaves: 4,
-                                        color_seed_offset: 100.0,
-                                        color_temperature: 0.5,
-                                        colors,
-                                        time_offset: rng.random_range(0.0..100.0),
-                                    });
-                                }
-
-                                XmasLightColor::Yellow => {
-                                    let colors = XMAS_LIGHT_YELLOW_COLORS.to_vec();
-
-                                    commands.entity(entity).insert(FlickeringLight {
-                                        seed: rng.random_range(0.0..1000.0),
-                                        intensity_amplitude: 0.1,
-                                        intensity_frequency: 2.0,
-                                        intensity_min: 0.15,
-                                        intensity_octaves: 2,
-                                        color_frequency: 10.0,
-                                        color_octaves: 4,
-                                        color_seed_offset: 100.0,
-                                        color_temperature: 0.5,
-                                        colors,
-                                        time_offset: rng.random_range(0.0..100.0),
-                                    });
-                                }
-
-                                XmasLightColor::Green => {
-                                    let colors = XMAS_LIGHT_GREEN_COLORS.to_vec();
-
-                                    commands.entity(entity).insert(FlickeringLight {
-                                        seed: rng.random_range(0.0..1000.0),
-                                        intensity_amplitude: 0.1,
-                                        intensity_frequency: 2.0,
-                                        intensity_min: 0.1,
-                                        intensity_octaves: 2,
-                                        color_frequency: 10.0,
-                                        color_octaves: 4,
-                                        color_seed_offset: 100.0,
-                                        color_temperature: 0.5,
-                                        colors,
-                                        time_offset: rng.random_range(0.0..100.0),
-                                    });
-                                }
-                            }
-                        }
-                    }
+    for (state, mut sprite, patterns) in switches {
+        // Switches don't click as they're spawned.
+        if state.is_added() {
+            continue;
+        }
 
-                    State::Off => {
-                        sprite.image = sprite_assets.switch_off.clone();
+        if patterns && *state == State::On {
+            sequencer.start();
+        }
 
-                        commands.spawn((
-                            AudioPlayer::new(audio_assets.off.clone()),
-                            PlaybackSettings::DESPAWN.with_volume(Volume::Linear(SWITCH_VOLUME)),
-                        ));
+        let (image, sound) = match *state {
+            State::On => (&sprite_assets.switch_on, &audio_assets.on),
+            State::Off => (&sprite_assets.switch_off, &audio_assets.off),
+        };
 
-                        commands.entity(entity).remove::<FlickeringLight>();
-                        light.intensity = 0.0;
-                    }
-                }
-            }
-        }
+        sprite.image = image.clone();
+        commands.spawn((
+            AudioPlayer::new(sound.clone()),
+            PlaybackSettings::DESPAWN.with_volume(Volume::Linear(SWITCH_VOLUME)),
+        ));
     }
 }
 
-// Attic light initialization.
+// House lights initialization.
 fn init(mut commands: Commands, asset_server: Res<AssetServer>) {
     // Load the sprite sheets.
     let sprites = SpriteAssets {
@@ -232,109 +181,132 @@ fn init(mut commands: Commands, asset_server: Res<AssetServer>) {
     };
     commands.insert_resource(audio);
 
-    // Parent position is the hidden switch.
-    let parent = commands
-        .spawn((
-            Switch,
-            State::Off,
-            Sprite {
-                image: sprites.switch_off,
-                ..default()
+    // Switch for the christmas lights along the eaves.
+    commands.spawn((
+        Switch {
+            circuit: Circuit::Eaves,
+        },
+        PatternSwitch,
+        State::Off,
+        Sprite {
+            image: sprites.switch_off.clone(),
+            ..default()
+        },
+        Transform::from_xyz(148.0, -50.0, 5.0),
+        DespawnOnExit(CardState::Running),
+        InRoom(Room::Living),
+        Interactable {
+            id: LIGHT_SWITCH_ID.to_string(),
+            height: 4.0,
+            width: 3.0,
+            ..default()
+        },
+    ));
+
+    // Switch for the attic light, up in the attic next to it.
+    commands.spawn((
+        Switch {
+            circuit: Circuit::Attic,
+        },
+        State::Off,
+        Sprite {
+            image: sprites.switch_off.clone(),
+            ..default()
+        },
+        Transform::from_xyz(136.0, 34.0, 5.0),
+        DespawnOnExit(CardState::Running),
+        InRoom(Room::Attic),
+        Interactable {
+            id: ATTIC_SWITCH_ID.to_string(),
+            height: 4.0,
+            width: 3.0,
+            ..default()
+        },
+    ));
+
+    // Timer outlet on the mains for the porch light.
+    commands.spawn((
+        Outlet {
+            supply: None,
+            circuit: Circuit::Porch,
+            schedule: Some(Schedule::new(PORCH_TIMER_OFF, PORCH_TIMER_ON)),
+        },
+        DespawnOnExit(CardState::Running),
+    ));
+
+    commands.spawn((
+        HouseLight {
+            preset: "fluorescent-buzz",
+            colors: ATTIC_LIGHT_COLORS,
+        },
+        Wired::new(Circuit::Attic),
+        Transform::from_xyz(128.0, 60.0, 5.0),
+        DespawnOnExit(CardState::Running),
+        InRoom(Room::Attic),
+        PointLight2d {
+            color: ATTIC_LIGHT_COLORS[0],
+            intensity: 0.0,
+            radius: 160.0,
+            cast_shadows: true,
+            ..default()
+        },
+    ));
+
+    // Porch light over the front door.
+    commands.spawn((
+        HouseLight {
+            preset: "candle",
+            colors: PORCH_LIGHT_COLORS,
+        },
+        Wired::new(Circuit::Porch),
+        Transform::from_xyz(-104.0, -40.0, 5.0),
+        DespawnOnExit(CardState::Running),
+        InRoom(Room::Porch),
+        PointLight2d {
+            color: PORCH_LIGHT_COLORS[0],
+            intensity: 0.0,
+            radius: 50.0,
+            cast_shadows: true,
+            ..default()
+        },
+    ));
+
+    // String of christmas lights along the eaves, repeating yellow, green and red.
+    let x_offset: f32 = -80.0;
+    let y: f32 = 5.0;
+    for point in 0..40i16 {
+        let x = f32::from(point).mul_add(7.0, x_offset);
+
+        let (image, colors, preset) = match point % 3 {
+            0 => (&sprites.xmas_light_yellow, XMAS_LIGHT_YELLOW_COLORS, "string-lights"),
+            1 => (
+                &sprites.xmas_light_green,
+                XMAS_LIGHT_GREEN_COLORS,
+                "string-lights-green",
+            ),
+            _ => (&sprites.xmas_light_red, XMAS_LIGHT_RED_COLORS, "string-lights"),
+        };
+
+        commands.spawn((
+            HouseLight { preset, colors },
+            Wired::new(Circuit::Eaves),
+            SequencedLight {
+                index: point as usize,
+                group: (point % 3) as usize,
             },
-            Transform::from_xyz(148.0, -50.0, 5.0),
-            Interactable {
-                id: INTERACTABLE_ID.to_string(),
-                height: 4.0,
-                width: 3.0,
+            Sprite {
+                image: image.clone(),
                 ..default()
             },
-        ))
-        .id();
-
-    // Spawn light, Local offset from switch (-21, 110, 0) → Global position (128, 60, 5)
-    let light_id = commands
-        .spawn((
-            AtticLight,
-            Transform::from_xyz(-20.0, 110.0, 0.0),
+            Transform::from_xyz(x, y, 7.0),
+            DespawnOnExit(CardState::Running),
             PointLight2d {
-                color: ATTIC_LIGHT_COLORS[0],
+                color: colors[0],
                 intensity: 0.0,
-                radius: 160.0,
+                radius: 15.0,
                 cast_shadows: true,
                 ..default()
             },
-        ))
-        .id();
-    commands.entity(parent).add_child(light_id);
-
-    let x_offset: f32 = -228.0;
-    let y: f32 = 55.0;
-    for point in 0..40i16 {
-        let x = f32::from(point).mul_add(7.0, x_offset);
-
-        match point % 3 {
-            0 => {
-                let light_id = commands
-                    .spawn((
-                        XmasLight(XmasLightColor::Yellow),
-                        Sprite {
-                            image: sprites.xmas_light_yellow.clone(),
-                            ..default()
-                        },
-                        Transform::from_xyz(x, y, 2.0),
-                        PointLight2d {
-                            color: XMAS_LIGHT_YELLOW_COLORS[0],
-                            intensity: 0.0,
-                            radius: 15.0,
-                            cast_shadows: true,
-                            ..default()
-                        },
-                    ))
-                    .id();
-                commands.entity(parent).add_child(light_id);
-            }
-
-            1 => {
-                let light_id = commands
-                    .spawn((
-                        XmasLight(XmasLightColor::Green),
-                        Sprite {
-                            image: sprites.xmas_light_green.clone(),
-                            ..default()
-                        },
-                        Transform::from_xyz(x, y, 2.0),
-                        PointLight2d {
-                            color: XMAS_LIGHT_GREEN_COLORS[0],
-                            intensity: 0.0,
-                            radius: 15.0,
-                            cast_shadows: true,
-                            ..default()
-                        },
-                    ))
-                    .id();
-                commands.entity(parent).add_child(light_id);
-            }
-
-            _ => {
-                let light_id = commands
-                    .spawn((
-                        XmasLight(XmasLightColor::Red),
-                        Sprite {
-                            image: sprites.xmas_light_red.clone(),
-                            ..default()
-                        },
-                        Transform::from_xyz(x, y, 2.0),
-                        PointLight2d {
-                            color: XMAS_LIGHT_RED_COLORS[0],
-                            intensity: 0.0,
-                            radius: 15.0,
-                            cast_shadows: true,
-                            ..default()
-                        },
-                    ))
-                    .id();
-                commands.entity(parent).add_child(light_id);
-            }
-        }
+        ));
     }
 }
@@ -1,7 +1,14 @@
 use bevy::input::touch::Touches;
 use bevy::prelude::*;
 
-use crate::interaction::{Highlight, Interactable, aabb_overlap};
+use crate::{
+    dialogue::DialogueState,
+    interaction::{Highlight, Hovered, Interactable, aabb_overlap},
+    menu::MenuState,
+    rooms::{Away, Fade},
+    settings::{KeyBindings, Settings},
+    timeline::CutsceneState,
+};
 
 // Click component for click feedback sprite.
 #[derive(Component)]
@@ -47,20 +54,28 @@ pub fn add_systems(app: &mut App) {
         Update,
         (
             handle_fade,
-            handle_keys,
-            handle_mouse_input,
-            handle_touch_input,
+            (handle_keys, handle_mouse_input, handle_touch_input).run_if(
+                in_state(MenuState::Closed)
+                    .and(in_state(CutsceneState::Idle))
+                    .and(in_state(DialogueState::Closed))
+                    .and(not(any_with_component::<Fade>)),
+            ),
             update_input_positions.before(detect_hover),
             detect_hover,
         ),
     );
 }
 
+// Check if a pointer is on a UI button so clicks aren't passed through to the world.
+fn over_button(buttons: &Query<&Interaction, With<Button>>) -> bool {
+    buttons.iter().any(|interaction| *interaction != Interaction::None)
+}
+
 // Process a world-space click/tap and emit appropriate events.
 fn process_world_click(
     commands: &mut Commands,
     world_pos: Vec2,
-    interactables: &Query<(&GlobalTransform, &Interactable)>,
+    interactables: &Query<(&GlobalTransform, &Interactable), Without<Away>>,
     input_events: &mut MessageWriter<InputEvent>,
 ) {
     let action: bool = interactables.iter().any(|(transform, interactable)| {
@@ -116,20 +131,26 @@ fn handle_fade(
     }
 }
 
-// Handle key input and send events.
-fn handle_keys(keyboard: Res<ButtonInput<KeyCode>>, mut input_events: MessageWriter<InputEvent>) {
+// Handle key input using the configured bindings and send events.
+fn handle_keys(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    mut input_events: MessageWriter<InputEvent>,
+) {
+    let KeyBindings { left, right, interact } = settings.bindings;
+
     // Check for key presses.
-    if keyboard.just_pressed(KeyCode::ArrowLeft) {
+    if keyboard.just_pressed(left) {
         input_events.write(InputEvent {
             direction: Some(Direction::Left),
             ..default()
         });
-    } else if keyboard.just_pressed(KeyCode::ArrowRight) {
+    } else if keyboard.just_pressed(right) {
         input_events.write(InputEvent {
             direction: Some(Direction::Right),
             ..default()
         });
-    } else if keyboard.just_pressed(KeyCode::ArrowUp) {
+    } else if keyboard.just_pressed(interact) {
         input_events.write(InputEvent {
             direction: Some(Direction::Up),
             ..default()
@@ -137,15 +158,13 @@ fn handle_keys(keyboard: Res<ButtonInput<KeyCode>>, mut input_events: MessageWri
     }
 
     // Check for final key releases.
-    if keyboard.just_released(KeyCode::ArrowLeft) && !keyboard.any_pressed(vec![KeyCode::ArrowLeft, KeyCode::ArrowUp]) {
+    if keyboard.just_released(left) && !keyboard.any_pressed(vec![left, interact]) {
         input_events.write(InputEvent::default());
     }
-    if keyboard.just_released(KeyCode::ArrowRight) && !keyboard.any_pressed(vec![KeyCode::ArrowLeft, KeyCode::ArrowUp])
-    {
+    if keyboard.just_released(right) && !keyboard.any_pressed(vec![left, interact]) {
         input_events.write(InputEvent::default());
     }
-    if keyboard.just_released(KeyCode::ArrowUp) && !keyboard.any_pressed(vec![KeyCode::ArrowLeft, KeyCode::ArrowRight])
-    {
+    if keyboard.just_released(interact) && !keyboard.any_pressed(vec![left, right]) {
         input_events.write(InputEvent::default());
     }
 }
@@ -156,10 +175,11 @@ fn handle_mouse_input(
     mouse_input: Res<ButtonInput<MouseButton>>,
     windows: Query<&Window>,
     camera_query: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
-    interactables: Query<(&GlobalTransform, &Interactable)>,
+    interactables: Query<(&GlobalTransform, &Interactable), Without<Away>>,
+    buttons: Query<&Interaction, With<Button>>,
     mut input_events: MessageWriter<InputEvent>,
 ) {
-    if mouse_input.just_pressed(MouseButton::Left) {
+    if mouse_input.just_pressed(MouseButton::Left) && !over_button(&buttons) {
         // Convert cursor position to world coordinates.
         let Ok(window) = windows.single() else {
             return;
@@ -184,9 +204,14 @@ fn handle_touch_input(
     touches: Res<Touches>,
     windows: Query<&Window>,
     camera_query: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
-    interactables: Query<(&GlobalTransform, &Interactable)>,
+    interactables: Query<(&GlobalTransform, &Interactable), Without<Away>>,
+    buttons: Query<&Interaction, With<Button>>,
     mut input_events: MessageWriter<InputEvent>,
 ) {
+    if over_button(&buttons) {
+        return;
+    }
+
     for touch in touches.iter_just_pressed() {
         // Convert touch position to world coordinates.
         let Ok(_window) = windows.single() else {
@@ -236,13 +261,13 @@ fn update_input_positions(
     }
 }
 
-// Detect overlap with interactable entities from any input and add or remove a Highlight component.
+// Detect overlap with interactable entities from any input and add or remove the Hovered and Highlight components.
 fn detect_hover(
     time: Res<Time>,
     mut commands: Commands,
     inputs: Res<InputWorldPositions>,
-    interactables: Query<(Entity, &GlobalTransform, &Interactable)>,
-    highlighted: Query<&Highlight>,
+    interactables: Query<(Entity, &GlobalTransform, &Interactable), Without<Away>>,
+    hovered: Query<&Hovered>,
 ) {
     for (entity, transform, interactable) in &interactables {
         let overlapping = inputs.positions.iter().any(|&input_pos| {
@@ -256,20 +281,23 @@ fn detect_hover(
             )
         });
 
-        let currently_highlighted = highlighted.contains(entity);
+        let currently_hovered = hovered.contains(entity);
 
-        match (currently_highlighted, overlapping) {
-            // New hover - add highlight if conditions met.
+        match (currently_hovered, overlapping) {
+            // New hover - add highlight.
             (false, true) => {
-                commands.entity(entity).insert(Highlight {
-                    elapsed_offset: time.elapsed_secs(),
-                    size: Vec2::new(interactable.sprite_width, interactable.sprite_height),
-                });
+                commands.entity(entity).insert((
+                    Hovered,
+                    Highlight {
+                        elapsed_offset: time.elapsed_secs(),
+                        size: Vec2::new(interactable.sprite_width, interactable.sprite_height),
+                    },
+                ));
             }
 
             // Hover ended - remove highlight.
             (true, false) => {
-                commands.entity(entity).remove::<Highlight>();
+                commands.entity(entity).remove::<(Hovered, Highlight)>();
             }
             _ => {}
         }
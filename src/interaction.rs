@@ -1,4 +1,7 @@
 use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::accessibility::AccessibilitySettings;
 
 // Added to Interactable entities when they should be highlighted.
 #[derive(Component)]
@@ -7,6 +10,10 @@ pub struct Highlight {
     pub size: Vec2,
 }
 
+// Added to Interactable entities while the pointer or a touch is over them.
+#[derive(Component)]
+pub struct Hovered;
+
 // Add to entities that can initiate interactions.
 #[derive(Component)]
 pub struct Interactor {
@@ -37,23 +44,42 @@ pub struct InteractionEvent {
     pub id: String,
 }
 
-#[derive(Clone, Component, Copy, PartialEq, Eq)]
+// Message sent to turn an interactable on or off by id without anyone interacting with it, e.g. from a timeline.
+#[derive(Message)]
+pub struct SetStateEvent {
+    pub id: String,
+    pub state: State,
+}
+
+#[derive(Clone, Component, Copy, Debug, Deserialize, PartialEq, Eq)]
 pub enum State {
     Off,
     On,
 }
 
+// Order of state changes in a frame, interactions and `SetStateEvent`s change states and then everything reacting to
+// the changes runs, no matter what changed them.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum StateSet {
+    Change,
+    Effects,
+}
+
 // Add the interaction systems.
 pub fn add_systems(app: &mut App) {
-    app.add_message::<InteractionEvent>().add_systems(
-        Update,
-        (
-            detect_overlaps,
-            handle_highlight,
-            handle_highlight_reset,
-            handle_highlight_state_change,
-        ),
-    );
+    app.add_message::<InteractionEvent>()
+        .add_message::<SetStateEvent>()
+        .configure_sets(Update, (StateSet::Change, StateSet::Effects).chain())
+        .add_systems(
+            Update,
+            (
+                detect_overlaps,
+                handle_set_state.in_set(StateSet::Change),
+                handle_highlight,
+                handle_highlight_reset,
+                handle_highlight_state_change.in_set(StateSet::Effects),
+            ),
+        );
 }
 
 // Axis-Aligned Bounding Box overlap detection.
@@ -134,9 +160,19 @@ fn detect_overlaps(
     }
 }
 
-// Apply a pulsing scale effect to highlighted sprites using custom_size.
-fn handle_highlight(time: Res<Time>, mut query: Query<(&mut Sprite, &Highlight)>) {
+// Apply a pulsing scale effect to highlighted sprites using custom_size, or a steady glow with reduced motion.
+fn handle_highlight(
+    time: Res<Time>,
+    accessibility: Res<AccessibilitySettings>,
+    mut query: Query<(&mut Sprite, &Highlight)>,
+) {
     for (mut sprite, highlight) in &mut query {
+        if accessibility.reduced_motion {
+            sprite.color = Color::srgba(1.2, 1.2, 1.2, 1.);
+            sprite.custom_size = Some(highlight.size);
+            continue;
+        }
+
         let pulse = (((time.elapsed_secs() - highlight.elapsed_offset) * 4.).sin() + 1.).mul_add(0.1, 1.);
         sprite.color = Color::srgba(pulse, pulse, pulse, 1.);
         let scale_factor = ((pulse - 1.) / 4.) + 1.;
@@ -154,6 +190,17 @@ fn handle_highlight_reset(mut removed: RemovedComponents<Highlight>, mut query:
     }
 }
 
+// Turn interactables on or off by id.
+fn handle_set_state(mut events: MessageReader<SetStateEvent>, mut query: Query<(&Interactable, &mut State)>) {
+    for event in events.read() {
+        for (interactable, mut state) in &mut query {
+            if interactable.id == event.id {
+                state.set_if_neq(event.state);
+            }
+        }
+    }
+}
+
 // Remove highlight component when an interactable is turned on.
 fn handle_highlight_state_change(
     mut commands: Commands,
@@ -0,0 +1,191 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::{data, settings::Settings};
+
+// Languages the card can be displayed in.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Language {
+    #[default]
+    English,
+    Spanish,
+    German,
+    Russian,
+}
+
+// Table of translated strings for one language, keyed by string id.
+#[derive(Asset, TypePath, Deserialize)]
+#[serde(transparent)]
+pub struct StringTable(HashMap<String, String>);
+
+// Translated strings for the current language.
+#[derive(Resource, Default)]
+pub struct Localization {
+    strings: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+// Text kept translated into the current language.
+#[derive(Component)]
+pub struct Localized(pub &'static str);
+
+#[derive(Clone, Resource)]
+struct LocaleAssets {
+    tables: Vec<(Language, Handle<StringTable>)>,
+    font: Handle<Font>,
+}
+
+pub const LANGUAGES: [Language; 4] = [
+    Language::English,
+    Language::Spanish,
+    Language::German,
+    Language::Russian,
+];
+
+impl Language {
+    // ISO 639-1 language code, also used for the string file name.
+    pub const fn code(self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::Spanish => "es",
+            Self::German => "de",
+            Self::Russian => "ru",
+        }
+    }
+
+    // The language name as written in that language.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::English => "English",
+            Self::Spanish => "Español",
+            Self::German => "Deutsch",
+            Self::Russian => "Русский",
+        }
+    }
+
+    // Find the language for a locale like "de-DE" or "ru_RU.UTF-8".
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        let code = locale.get(..2)?.to_lowercase();
+        LANGUAGES.into_iter().find(|language| language.code() == code)
+    }
+
+    // Cycle through the available languages.
+    pub fn step(self, step: i32) -> Self {
+        let index = LANGUAGES.iter().position(|&language| language == self).unwrap_or(0);
+        LANGUAGES[(index as i32 + step).rem_euclid(LANGUAGES.len() as i32) as usize]
+    }
+}
+
+impl Localization {
+    // Get a translated string, falling back to English and then the key itself.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .map_or(key, String::as_str)
+    }
+
+    // Whether the string files have loaded.
+    pub fn is_loaded(&self) -> bool {
+        !self.fallback.is_empty()
+    }
+
+    // Get a translated string with its `{name}` placeholders replaced.
+    pub fn format(&self, key: &str, args: &[(&str, &str)]) -> String {
+        args.iter().fold(self.get(key).to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), value)
+        })
+    }
+}
+
+// Add the localization systems.
+pub fn add_systems(app: &mut App) {
+    data::register::<StringTable>(app, &["strings.ron"]);
+
+    app.init_resource::<Localization>()
+        .add_systems(Startup, init)
+        .add_systems(
+            Update,
+            (handle_tables, handle_localized.after(handle_tables), handle_fonts),
+        );
+}
+
+// Use the font with non-Latin glyphs for all text.
+fn handle_fonts(assets: Res<LocaleAssets>, mut query: Query<&mut TextFont, Added<TextFont>>) {
+    for mut font in &mut query {
+        font.font = assets.font.clone();
+    }
+}
+
+// Update localized text when it's added or the translations change.
+fn handle_localized(localization: Res<Localization>, mut query: Query<(Ref<Localized>, &mut Text)>) {
+    // Keep the initial text until the string files have loaded.
+    if !localization.is_loaded() {
+        return;
+    }
+
+    for (localized, mut text) in &mut query {
+        if localization.is_changed() || localized.is_added() {
+            text.0 = localization.get(localized.0).to_string();
+        }
+    }
+}
+
+// Rebuild the translations when the language changes or a string file is (re)loaded.
+fn handle_tables(
+    settings: Res<Settings>,
+    assets: Res<LocaleAssets>,
+    tables: Res<Assets<StringTable>>,
+    mut events: MessageReader<AssetEvent<StringTable>>,
+    mut localization: ResMut<Localization>,
+) {
+    let reloaded = events.read().count() > 0;
+    if !reloaded && !settings.is_changed() {
+        return;
+    }
+
+    let strings = |language: Language| {
+        assets
+            .tables
+            .iter()
+            .find(|(table_language, _)| *table_language == language)
+            .and_then(|(_, handle)| tables.get(handle))
+            .map(|table| table.0.clone())
+            .unwrap_or_default()
+    };
+    localization.strings = strings(settings.language);
+    localization.fallback = strings(Language::English);
+}
+
+// Detect the user's language from the browser.
+#[cfg(target_arch = "wasm32")]
+fn system_locale() -> Option<String> {
+    web_sys::window()?.navigator().language()
+}
+
+// Detect the user's language from the environment.
+#[cfg(not(target_arch = "wasm32"))]
+fn system_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .find_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty()))
+}
+
+// Load the string files and start in the user's language.
+fn init(mut commands: Commands, asset_server: Res<AssetServer>, mut settings: ResMut<Settings>) {
+    commands.insert_resource(LocaleAssets {
+        tables: LANGUAGES
+            .into_iter()
+            .map(|language| {
+                let handle = asset_server.load(format!("locales/{}.strings.ron", language.code()));
+                (language, handle)
+            })
+            .collect(),
+        font: asset_server.load("fonts/DejaVuSans.ttf"),
+    });
+
+    if let Some(language) = system_locale().as_deref().and_then(Language::from_locale) {
+        settings.language = language;
+    }
+}
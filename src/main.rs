@@ -1,23 +1,41 @@
 //! Animate a sprite in response to a keyboard event.
 
+mod accessibility;
+mod achievements;
 mod animation;
 mod app;
 mod background;
 mod camera;
 mod chair;
+mod data;
+#[cfg(feature = "dev")]
+mod debug;
+mod dialogue;
 mod fireplace;
 mod flickering_light;
 mod house;
 mod house_lights;
 mod input;
 mod interaction;
+mod localization;
+mod menu;
 mod noise;
+mod particles;
+mod rooms;
 mod santa;
+mod sequencer;
+mod settings;
+mod sky;
 mod snow;
 mod snowman;
 mod stereo;
+mod surface;
 mod theman;
+mod timeline;
+mod tooltip;
 mod tree;
+mod warmth;
+mod wiring;
 
 fn main() {
     app::run_app();
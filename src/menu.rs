@@ -0,0 +1,507 @@
+use bevy::prelude::*;
+
+use crate::{
+    accessibility::AccessibilitySettings,
+    app::CardState,
+    localization::{Localization, Localized},
+    settings::Settings,
+};
+
+// Whether the pause menu is showing.
+#[derive(States, Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum MenuState {
+    #[default]
+    Closed,
+    Open,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum MenuItem {
+    Resume,
+    Volume,
+    BindLeft,
+    BindRight,
+    BindInteract,
+    ReducedMotion,
+    HighContrast,
+    Framing,
+    Language,
+    Reset,
+}
+
+// Menu row button.
+#[derive(Component)]
+struct MenuRow(MenuItem);
+
+// Text showing a menu row's label and value.
+#[derive(Component)]
+struct MenuLabel(MenuItem);
+
+// Arrow button to change a menu row's value with a pointer.
+#[derive(Component)]
+struct MenuStep {
+    item: MenuItem,
+    step: i32,
+}
+
+// On-screen button to open and close the menu.
+#[derive(Component)]
+struct PauseButton;
+
+// Added to sounds paused by the menu so only they are resumed.
+#[derive(Component)]
+struct MenuPaused;
+
+// The selected menu row and the key binding waiting for a key press.
+#[derive(Resource, Default)]
+struct MenuSelection {
+    index: usize,
+    rebinding: Option<MenuItem>,
+}
+
+// Message sent to activate (step 0) or change the value of a menu item.
+#[derive(Message)]
+struct MenuCommand {
+    item: MenuItem,
+    step: i32,
+}
+
+const MENU_ITEMS: [MenuItem; 10] = [
+    MenuItem::Resume,
+    MenuItem::Volume,
+    MenuItem::BindLeft,
+    MenuItem::BindRight,
+    MenuItem::BindInteract,
+    MenuItem::ReducedMotion,
+    MenuItem::HighContrast,
+    MenuItem::Framing,
+    MenuItem::Language,
+    MenuItem::Reset,
+];
+
+const FONT_SIZE: f32 = 16.0;
+const SELECTED_COLOR: Color = Color::srgb(1.0, 0.85, 0.3);
+const OVERLAY_COLOR: Color = Color::srgba(0.0, 0.0, 0.05, 0.7);
+const BUTTON_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.1);
+
+// Add the menu systems.
+pub fn add_systems(app: &mut App) {
+    app.init_state::<MenuState>()
+        .init_resource::<MenuSelection>()
+        .add_message::<MenuCommand>()
+        .add_systems(Startup, init)
+        .add_systems(OnEnter(MenuState::Open), (pause, spawn_menu))
+        .add_systems(OnExit(MenuState::Open), resume)
+        .add_systems(
+            Update,
+            (
+                handle_toggle,
+                (
+                    handle_rebinding,
+                    handle_navigation,
+                    handle_pointer,
+                    handle_commands,
+                    handle_labels,
+                )
+                    .run_if(in_state(MenuState::Open)),
+            )
+                .chain(),
+        );
+}
+
+// Apply a menu command to the settings or card.
+fn handle_commands(
+    mut events: MessageReader<MenuCommand>,
+    mut settings: ResMut<Settings>,
+    mut accessibility: ResMut<AccessibilitySettings>,
+    mut selection: ResMut<MenuSelection>,
+    mut next_menu: ResMut<NextState<MenuState>>,
+    mut next_card: ResMut<NextState<CardState>>,
+) {
+    for event in events.read() {
+        match (event.item, event.step) {
+            (MenuItem::Resume, 0) => {
+                next_menu.set(MenuState::Closed);
+            }
+
+            (MenuItem::Volume, step) => {
+                settings.step_volume(step);
+            }
+
+            (MenuItem::BindLeft | MenuItem::BindRight | MenuItem::BindInteract, 0) => {
+                selection.rebinding = Some(event.item);
+            }
+
+            (MenuItem::ReducedMotion, _) => {
+                accessibility.reduced_motion = !accessibility.reduced_motion;
+            }
+
+            (MenuItem::HighContrast, _) => {
+                accessibility.high_contrast = !accessibility.high_contrast;
+            }
+
+            (MenuItem::Framing, step) => {
+                settings.framing = settings.framing.step(if step == 0 { 1 } else { step });
+            }
+
+            (MenuItem::Language, step) => {
+                settings.language = settings.language.step(if step == 0 { 1 } else { step });
+            }
+
+            (MenuItem::Reset, 0) => {
+                next_card.set(CardState::Resetting);
+                next_menu.set(MenuState::Closed);
+            }
+
+            _ => {}
+        }
+    }
+}
+
+// Keep the row text and colors up to date with the settings and selection.
+fn handle_labels(
+    settings: Res<Settings>,
+    accessibility: Res<AccessibilitySettings>,
+    localization: Res<Localization>,
+    selection: Res<MenuSelection>,
+    mut query: Query<(&MenuLabel, &mut Text, &mut TextColor)>,
+) {
+    for (label, mut text, mut color) in &mut query {
+        let value = label_text(label.0, &settings, &accessibility, &localization, &selection);
+        if text.0 != value {
+            text.0 = value;
+        }
+
+        let selected = MENU_ITEMS[selection.index] == label.0;
+        let value = if selected { SELECTED_COLOR } else { Color::WHITE };
+        if color.0 != value {
+            color.0 = value;
+        }
+    }
+}
+
+// Move the selection and change values with the keyboard or a gamepad.
+fn handle_navigation(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut selection: ResMut<MenuSelection>,
+    mut commands: MessageWriter<MenuCommand>,
+) {
+    if selection.rebinding.is_some() {
+        return;
+    }
+
+    let pressed = |key: KeyCode, button: GamepadButton| {
+        keyboard.just_pressed(key) || gamepads.iter().any(|gamepad| gamepad.just_pressed(button))
+    };
+
+    if pressed(KeyCode::ArrowUp, GamepadButton::DPadUp) {
+        selection.index = (selection.index + MENU_ITEMS.len() - 1) % MENU_ITEMS.len();
+    } else if pressed(KeyCode::ArrowDown, GamepadButton::DPadDown) {
+        selection.index = (selection.index + 1) % MENU_ITEMS.len();
+    } else if pressed(KeyCode::ArrowLeft, GamepadButton::DPadLeft) {
+        commands.write(MenuCommand {
+            item: MENU_ITEMS[selection.index],
+            step: -1,
+        });
+    } else if pressed(KeyCode::ArrowRight, GamepadButton::DPadRight) {
+        commands.write(MenuCommand {
+            item: MENU_ITEMS[selection.index],
+            step: 1,
+        });
+    } else if pressed(KeyCode::Enter, GamepadButton::South) || keyboard.just_pressed(KeyCode::Space) {
+        commands.write(MenuCommand {
+            item: MENU_ITEMS[selection.index],
+            step: 0,
+        });
+    }
+}
+
+// Select and activate rows with a mouse or touch.
+fn handle_pointer(
+    rows: Query<(&Interaction, &MenuRow), Changed<Interaction>>,
+    steps: Query<(&Interaction, &MenuStep), Changed<Interaction>>,
+    mut selection: ResMut<MenuSelection>,
+    mut commands: MessageWriter<MenuCommand>,
+) {
+    if selection.rebinding.is_some() {
+        return;
+    }
+
+    for (interaction, row) in &rows {
+        let index = MENU_ITEMS.iter().position(|&item| item == row.0).unwrap_or(0);
+
+        match interaction {
+            Interaction::Hovered => {
+                selection.index = index;
+            }
+
+            Interaction::Pressed => {
+                selection.index = index;
+                commands.write(MenuCommand { item: row.0, step: 0 });
+            }
+
+            Interaction::None => {}
+        }
+    }
+
+    for (interaction, step) in &steps {
+        if *interaction == Interaction::Pressed {
+            commands.write(MenuCommand {
+                item: step.item,
+                step: step.step,
+            });
+        }
+    }
+}
+
+// Assign the next pressed key to the binding being changed, escape cancels.
+fn handle_rebinding(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut selection: ResMut<MenuSelection>,
+    mut settings: ResMut<Settings>,
+) {
+    let Some(item) = selection.rebinding else {
+        return;
+    };
+    let Some(&key) = keyboard.get_just_pressed().next() else {
+        return;
+    };
+
+    if key != KeyCode::Escape {
+        match item {
+            MenuItem::BindLeft => settings.bindings.left = key,
+            MenuItem::BindRight => settings.bindings.right = key,
+            MenuItem::BindInteract => settings.bindings.interact = key,
+            _ => {}
+        }
+    }
+    selection.rebinding = None;
+}
+
+// Open or close the menu with escape, the gamepad start button or the on-screen button.
+fn handle_toggle(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    buttons: Query<&Interaction, (With<PauseButton>, Changed<Interaction>)>,
+    selection: Res<MenuSelection>,
+    state: Res<State<MenuState>>,
+    mut next_state: ResMut<NextState<MenuState>>,
+) {
+    // Escape cancels key binding instead of closing.
+    if selection.rebinding.is_some() {
+        return;
+    }
+
+    if keyboard.just_pressed(KeyCode::Escape)
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.just_pressed(GamepadButton::Start))
+        || buttons.iter().any(|interaction| *interaction == Interaction::Pressed)
+    {
+        next_state.set(match state.get() {
+            MenuState::Open => MenuState::Closed,
+            MenuState::Closed => MenuState::Open,
+        });
+    }
+}
+
+// Translated text for a menu row.
+fn label_text(
+    item: MenuItem,
+    settings: &Settings,
+    accessibility: &AccessibilitySettings,
+    localization: &Localization,
+    selection: &MenuSelection,
+) -> String {
+    let toggle = |key: &str, value: bool| {
+        let value = localization.get(if value { "common.on" } else { "common.off" });
+        localization.format(key, &[("value", value)])
+    };
+    let binding = |key: &str, code: KeyCode| {
+        let value = if selection.rebinding == Some(item) {
+            localization.get("menu.press_key").to_string()
+        } else {
+            format!("{code:?}")
+        };
+        localization.format(key, &[("key", &value)])
+    };
+
+    match item {
+        MenuItem::Resume => localization.get("menu.resume").to_string(),
+        MenuItem::Volume => {
+            localization.format("menu.volume", &[("value", &format!("{:.0}%", settings.volume * 100.0))])
+        }
+        MenuItem::BindLeft => binding("menu.move_left", settings.bindings.left),
+        MenuItem::BindRight => binding("menu.move_right", settings.bindings.right),
+        MenuItem::BindInteract => binding("menu.interact", settings.bindings.interact),
+        MenuItem::ReducedMotion => toggle("menu.reduced_motion", accessibility.reduced_motion),
+        MenuItem::HighContrast => toggle("menu.high_contrast", accessibility.high_contrast),
+        MenuItem::Framing => {
+            localization.format("menu.framing", &[("value", localization.get(settings.framing.key()))])
+        }
+        MenuItem::Language => localization.format("menu.language", &[("value", settings.language.name())]),
+        MenuItem::Reset => localization.get("menu.reset").to_string(),
+    }
+}
+
+// Freeze the card and its sounds while the menu is open.
+fn pause(
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    mut selection: ResMut<MenuSelection>,
+    sinks: Query<(Entity, &AudioSink)>,
+    spatial_sinks: Query<(Entity, &SpatialAudioSink)>,
+) {
+    time.pause();
+    *selection = MenuSelection::default();
+
+    for (entity, sink) in &sinks {
+        if !sink.is_paused() {
+            sink.pause();
+            commands.entity(entity).insert(MenuPaused);
+        }
+    }
+    for (entity, sink) in &spatial_sinks {
+        if !sink.is_paused() {
+            sink.pause();
+            commands.entity(entity).insert(MenuPaused);
+        }
+    }
+}
+
+// Unfreeze the card and resume the sounds paused by the menu.
+fn resume(
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    sinks: Query<(Entity, &AudioSink), With<MenuPaused>>,
+    spatial_sinks: Query<(Entity, &SpatialAudioSink), With<MenuPaused>>,
+) {
+    time.unpause();
+
+    for (entity, sink) in &sinks {
+        sink.play();
+        commands.entity(entity).remove::<MenuPaused>();
+    }
+    for (entity, sink) in &spatial_sinks {
+        sink.play();
+        commands.entity(entity).remove::<MenuPaused>();
+    }
+}
+
+// Build the menu overlay, despawned when the menu closes.
+fn spawn_menu(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    accessibility: Res<AccessibilitySettings>,
+    localization: Res<Localization>,
+    selection: Res<MenuSelection>,
+) {
+    let overlay = commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: percent(100),
+                height: percent(100),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: px(4),
+                ..default()
+            },
+            BackgroundColor(OVERLAY_COLOR),
+            GlobalZIndex(10),
+            DespawnOnExit(MenuState::Open),
+        ))
+        .id();
+
+    for item in MENU_ITEMS {
+        // Items with a value get arrow buttons for pointer input.
+        let has_value = matches!(
+            item,
+            MenuItem::Volume
+                | MenuItem::ReducedMotion
+                | MenuItem::HighContrast
+                | MenuItem::Framing
+                | MenuItem::Language
+        );
+
+        let row = commands
+            .spawn((
+                Button,
+                MenuRow(item),
+                Node {
+                    padding: UiRect::axes(px(8), px(2)),
+                    column_gap: px(8),
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                BackgroundColor(BUTTON_COLOR),
+            ))
+            .id();
+
+        if has_value {
+            let left = spawn_step(&mut commands, item, -1);
+            commands.entity(row).add_child(left);
+        }
+
+        let label = commands
+            .spawn((
+                MenuLabel(item),
+                Text::new(label_text(item, &settings, &accessibility, &localization, &selection)),
+                TextFont::from_font_size(FONT_SIZE),
+                TextColor(Color::WHITE),
+            ))
+            .id();
+        commands.entity(row).add_child(label);
+
+        if has_value {
+            let right = spawn_step(&mut commands, item, 1);
+            commands.entity(row).add_child(right);
+        }
+
+        commands.entity(overlay).add_child(row);
+    }
+}
+
+// Spawn an arrow button that steps a menu item's value.
+fn spawn_step(commands: &mut Commands, item: MenuItem, step: i32) -> Entity {
+    commands
+        .spawn((
+            Button,
+            MenuStep { item, step },
+            Node {
+                padding: UiRect::horizontal(px(4)),
+                ..default()
+            },
+            BackgroundColor(BUTTON_COLOR),
+            children![(
+                Text::new(if step < 0 { "<" } else { ">" }),
+                TextFont::from_font_size(FONT_SIZE),
+            )],
+        ))
+        .id()
+}
+
+// Menu initialization.
+fn init(mut commands: Commands) {
+    // On-screen pause button in the upper left for pointer and touch users.
+    commands.spawn((
+        Button,
+        PauseButton,
+        Node {
+            position_type: PositionType::Absolute,
+            top: px(12),
+            left: px(12),
+            padding: UiRect::axes(px(6), px(2)),
+            ..default()
+        },
+        BackgroundColor(BUTTON_COLOR),
+        GlobalZIndex(11),
+        children![(
+            Text::new("menu"),
+            Localized("menu.open"),
+            TextFont::from_font_size(FONT_SIZE)
+        )],
+    ));
+}
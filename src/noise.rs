@@ -1,9 +1,10 @@
-// Fractal Browning Motion with Perlin Noise implementation for 2D continuous noise generation.
+// Seeded gradient and value noise for continuous pseudo-random values, layered into fractal noise.
 // References:
 //   - Perlin noise: https://mrl.cs.nyu.edu/~perlin/noise/
+//   - Simplex noise: https://weber.itn.liu.se/~stegu/simplexnoise/simplexnoise.pdf
 //   - Fractal browning motion: https://en.wikipedia.org/wiki/Fractional_Brownian_motion
 
-// Permutation table for deterministic pseudo-randomness.
+// Permutation table for deterministic pseudo-randomness, used by the unseeded generator.
 const PERMUTATION: [u8; 256] = [
     151, 160, 137, 91, 90, 15, 131, 13, 201, 95, 96, 53, 194, 233, 7, 225, 140, 36, 103, 30, 69, 142, 8, 99, 37, 240,
     21, 10, 23, 190, 6, 148, 247, 120, 234, 75, 0, 26, 197, 62, 94, 252, 219, 203, 117, 35, 11, 32, 57, 177, 33, 88,
@@ -18,24 +19,419 @@ const PERMUTATION: [u8; 256] = [
     78, 66, 215, 61, 156, 180,
 ];
 
-// Noise generation combining fractal brownian motion and multiple octaves of Perlin noise.
-// NOTE: Each octave has double the frequency and half the amplitude of the previous.
-pub fn generate(x: f32, y: f32, octaves: u32) -> f32 {
-    let mut total = 0.0;
-    let mut frequency = 1.0;
-    let mut amplitude = 1.0;
-    let mut max_value = 0.0;
+// Generator using the fixed permutation table.
+const DEFAULT: Noise = Noise::from_table(PERMUTATION);
 
-    for _ in 0..octaves {
-        total += perlin_2d(x * frequency, y * frequency) * amplitude;
+// Skewing and unskewing factors for simplex noise, (sqrt(n + 1) - 1) / n and (1 - 1 / sqrt(n + 1)) / n.
+const F2: f32 = 0.366_025_42;
+const G2: f32 = 0.211_324_87;
+const F3: f32 = 1.0 / 3.0;
+const G3: f32 = 1.0 / 6.0;
 
-        max_value += amplitude;
-        amplitude *= 0.5;
-        frequency *= 2.0;
+// Scales that bring each noise function to the range [-1, 1].
+const PERLIN_1D_SCALE: f32 = 2.0;
+const SIMPLEX_1D_SCALE: f32 = 0.395;
+const SIMPLEX_2D_SCALE: f32 = 70.0;
+const SIMPLEX_3D_SCALE: f32 = 32.0;
+
+// Basic noise function that fractal noise is layered from.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Basis {
+    #[default]
+    Perlin,
+    // Cheaper in higher dimensions and without Perlin's grid aligned artifacts.
+    Simplex,
+    // Interpolated random values, blockier than the gradient noises.
+    Value,
+}
+
+// How the octaves of fractal noise are combined.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Mode {
+    // Fractal brownian motion, in the range [-1, 1].
+    #[default]
+    Fbm,
+    // Sharp crests where the basis crosses zero, in the range [0, 1].
+    Ridged,
+    // Billowy folds from the absolute value of each octave, in the range [0, 1].
+    Turbulence,
+}
+
+// Settings for layering octaves of a basis noise.
+// NOTE: each octave has `lacunarity` times the frequency and `gain` times the amplitude of the previous.
+#[derive(Clone, Copy, Debug)]
+pub struct Fractal {
+    pub basis: Basis,
+    pub mode: Mode,
+    pub octaves: u32,
+    pub lacunarity: f32,
+    pub gain: f32,
+}
+
+// Noise generator with its own permutation table, so differently seeded generators give unrelated noise.
+#[derive(Clone)]
+pub struct Noise {
+    // The permutation table repeated twice to avoid wrapping indices when hashing corners.
+    perm: [u8; 512],
+}
+
+impl Default for Fractal {
+    fn default() -> Self {
+        Self {
+            basis: Basis::Perlin,
+            mode: Mode::Fbm,
+            octaves: 4,
+            lacunarity: 2.0,
+            gain: 0.5,
+        }
+    }
+}
+
+impl Default for Noise {
+    fn default() -> Self {
+        DEFAULT
+    }
+}
+
+impl Noise {
+    // Generator with a permutation table shuffled by the seed.
+    // NOTE: this is a const fn so fixed generators can be statics, which is why it loops with `while`.
+    pub const fn new(seed: u64) -> Self {
+        let mut table = [0; 256];
+        let mut i = 0;
+        while i < table.len() {
+            table[i] = i as u8;
+            i += 1;
+        }
+
+        // Fisher-Yates shuffle driven by splitmix64.
+        let mut state = seed;
+        let mut i = table.len() - 1;
+        while i > 0 {
+            state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^= z >> 31;
+            table.swap(i, (z % (i as u64 + 1)) as usize);
+            i -= 1;
+        }
+
+        Self::from_table(table)
+    }
+
+    const fn from_table(table: [u8; 256]) -> Self {
+        let mut perm = [0; 512];
+        let mut i = 0;
+        while i < perm.len() {
+            perm[i] = table[i & 255];
+            i += 1;
+        }
+        Self { perm }
+    }
+
+    // Layered noise along a line.
+    pub fn fractal_1d(&self, fractal: &Fractal, x: f32) -> f32 {
+        layer(fractal, |frequency| self.sample_1d(fractal.basis, x * frequency))
+    }
+
+    // Layered noise over a plane.
+    pub fn fractal_2d(&self, fractal: &Fractal, x: f32, y: f32) -> f32 {
+        layer(fractal, |frequency| {
+            self.sample_2d(fractal.basis, x * frequency, y * frequency)
+        })
+    }
+
+    // Layered noise through a volume, or over a plane changing with time.
+    pub fn fractal_3d(&self, fractal: &Fractal, x: f32, y: f32, z: f32) -> f32 {
+        layer(fractal, |frequency| {
+            self.sample_3d(fractal.basis, x * frequency, y * frequency, z * frequency)
+        })
+    }
+
+    // Single octave of the basis noise along a line, in the range [-1, 1].
+    pub fn sample_1d(&self, basis: Basis, x: f32) -> f32 {
+        match basis {
+            Basis::Perlin => self.perlin_1d(x),
+            Basis::Simplex => self.simplex_1d(x),
+            Basis::Value => self.value_1d(x),
+        }
+    }
+
+    // Single octave of the basis noise over a plane, in the range [-1, 1].
+    pub fn sample_2d(&self, basis: Basis, x: f32, y: f32) -> f32 {
+        match basis {
+            Basis::Perlin => self.perlin_2d(x, y),
+            Basis::Simplex => self.simplex_2d(x, y),
+            Basis::Value => self.value_2d(x, y),
+        }
+    }
+
+    // Single octave of the basis noise through a volume, in the range [-1, 1].
+    pub fn sample_3d(&self, basis: Basis, x: f32, y: f32, z: f32) -> f32 {
+        match basis {
+            Basis::Perlin => self.perlin_3d(x, y, z),
+            Basis::Simplex => self.simplex_3d(x, y, z),
+            Basis::Value => self.value_3d(x, y, z),
+        }
+    }
+
+    // 1D Perlin noise.
+    pub fn perlin_1d(&self, x: f32) -> f32 {
+        let xi = lattice(x);
+        let x_rel = x - x.floor();
+
+        let a = grad_1d(self.perm[xi], x_rel);
+        let b = grad_1d(self.perm[xi + 1], x_rel - 1.0);
+
+        lerp(fade(x_rel), a, b) * PERLIN_1D_SCALE
+    }
+
+    // 2D Perlin noise.
+    pub fn perlin_2d(&self, x: f32, y: f32) -> f32 {
+        let (xi, yi) = (lattice(x), lattice(y));
+
+        // Relative position within the cell.
+        let x_rel = x - x.floor();
+        let y_rel = y - y.floor();
+
+        // Fade curves for smooth interpolation.
+        let u = fade(x_rel);
+        let v = fade(y_rel);
+
+        // Hash coordinates of the 4 square corners.
+        let a = self.hash(xi) + yi;
+        let b = self.hash(xi + 1) + yi;
+
+        // Blend results from 4 corners of the square.
+        lerp(
+            v,
+            lerp(
+                u,
+                grad_2d(self.perm[a], x_rel, y_rel),
+                grad_2d(self.perm[b], x_rel - 1.0, y_rel),
+            ),
+            lerp(
+                u,
+                grad_2d(self.perm[a + 1], x_rel, y_rel - 1.0),
+                grad_2d(self.perm[b + 1], x_rel - 1.0, y_rel - 1.0),
+            ),
+        )
+    }
+
+    // 3D Perlin noise.
+    pub fn perlin_3d(&self, x: f32, y: f32, z: f32) -> f32 {
+        let (xi, yi, zi) = (lattice(x), lattice(y), lattice(z));
+
+        // Relative position within the cell.
+        let x_rel = x - x.floor();
+        let y_rel = y - y.floor();
+        let z_rel = z - z.floor();
+
+        // Fade curves for smooth interpolation.
+        let u = fade(x_rel);
+        let v = fade(y_rel);
+        let w = fade(z_rel);
+
+        // Hash coordinates of the 8 cube corners.
+        let a = self.hash(xi) + yi;
+        let aa = self.hash(a) + zi;
+        let ab = self.hash(a + 1) + zi;
+        let b = self.hash(xi + 1) + yi;
+        let ba = self.hash(b) + zi;
+        let bb = self.hash(b + 1) + zi;
+
+        // Blend results from 8 corners of the cube.
+        lerp(
+            w,
+            lerp(
+                v,
+                lerp(
+                    u,
+                    grad_3d(self.perm[aa], x_rel, y_rel, z_rel),
+                    grad_3d(self.perm[ba], x_rel - 1.0, y_rel, z_rel),
+                ),
+                lerp(
+                    u,
+                    grad_3d(self.perm[ab], x_rel, y_rel - 1.0, z_rel),
+                    grad_3d(self.perm[bb], x_rel - 1.0, y_rel - 1.0, z_rel),
+                ),
+            ),
+            lerp(
+                v,
+                lerp(
+                    u,
+                    grad_3d(self.perm[aa + 1], x_rel, y_rel, z_rel - 1.0),
+                    grad_3d(self.perm[ba + 1], x_rel - 1.0, y_rel, z_rel - 1.0),
+                ),
+                lerp(
+                    u,
+                    grad_3d(self.perm[ab + 1], x_rel, y_rel - 1.0, z_rel - 1.0),
+                    grad_3d(self.perm[bb + 1], x_rel - 1.0, y_rel - 1.0, z_rel - 1.0),
+                ),
+            ),
+        )
+    }
+
+    // 1D simplex noise, the sum of the two surrounding corners' falloff.
+    pub fn simplex_1d(&self, x: f32) -> f32 {
+        let xi = lattice(x);
+        let x0 = x - x.floor();
+        let x1 = x0 - 1.0;
+
+        let n0 = falloff(1.0 - x0 * x0) * grad_simplex_1d(self.perm[xi], x0);
+        let n1 = falloff(1.0 - x1 * x1) * grad_simplex_1d(self.perm[xi + 1], x1);
+
+        (n0 + n1) * SIMPLEX_1D_SCALE
+    }
+
+    // 2D simplex noise, the sum of the three corners of the triangle the point falls in.
+    pub fn simplex_2d(&self, x: f32, y: f32) -> f32 {
+        // Skew the input space to find the triangle's cell.
+        let s = (x + y) * F2;
+        let i = (x + s).floor();
+        let j = (y + s).floor();
+
+        // Unskew the cell origin back to find the distance to the first corner.
+        let t = (i + j) * G2;
+        let x0 = x - (i - t);
+        let y0 = y - (j - t);
+
+        // Lower or upper triangle of the cell.
+        let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+
+        let x1 = x0 - i1 as f32 + G2;
+        let y1 = y0 - j1 as f32 + G2;
+        let x2 = x0 - 1.0 + 2.0 * G2;
+        let y2 = y0 - 1.0 + 2.0 * G2;
+
+        let (ii, jj) = (lattice(i), lattice(j));
+        let g0 = self.perm[ii + self.hash(jj)];
+        let g1 = self.perm[ii + i1 + self.hash(jj + j1)];
+        let g2 = self.perm[ii + 1 + self.hash(jj + 1)];
+
+        let n0 = falloff(0.5 - x0 * x0 - y0 * y0) * grad_3d(g0, x0, y0, 0.0);
+        let n1 = falloff(0.5 - x1 * x1 - y1 * y1) * grad_3d(g1, x1, y1, 0.0);
+        let n2 = falloff(0.5 - x2 * x2 - y2 * y2) * grad_3d(g2, x2, y2, 0.0);
+
+        (n0 + n1 + n2) * SIMPLEX_2D_SCALE
     }
 
-    // Normalize to [-1, 1] range
-    total / max_value
+    // 3D simplex noise, the sum of the four corners of the tetrahedron the point falls in.
+    pub fn simplex_3d(&self, x: f32, y: f32, z: f32) -> f32 {
+        // Skew the input space to find the tetrahedron's cell.
+        let s = (x + y + z) * F3;
+        let i = (x + s).floor();
+        let j = (y + s).floor();
+        let k = (z + s).floor();
+
+        // Unskew the cell origin back to find the distance to the first corner.
+        let t = (i + j + k) * G3;
+        let x0 = x - (i - t);
+        let y0 = y - (j - t);
+        let z0 = z - (k - t);
+
+        // Offsets of the second and third corners, depending on which of the six tetrahedra the point is in.
+        let ((i1, j1, k1), (i2, j2, k2)) = if x0 >= y0 {
+            if y0 >= z0 {
+                ((1, 0, 0), (1, 1, 0))
+            } else if x0 >= z0 {
+                ((1, 0, 0), (1, 0, 1))
+            } else {
+                ((0, 0, 1), (1, 0, 1))
+            }
+        } else if y0 < z0 {
+            ((0, 0, 1), (0, 1, 1))
+        } else if x0 < z0 {
+            ((0, 1, 0), (0, 1, 1))
+        } else {
+            ((0, 1, 0), (1, 1, 0))
+        };
+
+        let x1 = x0 - i1 as f32 + G3;
+        let y1 = y0 - j1 as f32 + G3;
+        let z1 = z0 - k1 as f32 + G3;
+        let x2 = x0 - i2 as f32 + 2.0 * G3;
+        let y2 = y0 - j2 as f32 + 2.0 * G3;
+        let z2 = z0 - k2 as f32 + 2.0 * G3;
+        let x3 = x0 - 1.0 + 3.0 * G3;
+        let y3 = y0 - 1.0 + 3.0 * G3;
+        let z3 = z0 - 1.0 + 3.0 * G3;
+
+        let (ii, jj, kk) = (lattice(i), lattice(j), lattice(k));
+        let g0 = self.perm[ii + self.hash(jj + self.hash(kk))];
+        let g1 = self.perm[ii + i1 + self.hash(jj + j1 + self.hash(kk + k1))];
+        let g2 = self.perm[ii + i2 + self.hash(jj + j2 + self.hash(kk + k2))];
+        let g3 = self.perm[ii + 1 + self.hash(jj + 1 + self.hash(kk + 1))];
+
+        let n0 = falloff(0.6 - x0 * x0 - y0 * y0 - z0 * z0) * grad_3d(g0, x0, y0, z0);
+        let n1 = falloff(0.6 - x1 * x1 - y1 * y1 - z1 * z1) * grad_3d(g1, x1, y1, z1);
+        let n2 = falloff(0.6 - x2 * x2 - y2 * y2 - z2 * z2) * grad_3d(g2, x2, y2, z2);
+        let n3 = falloff(0.6 - x3 * x3 - y3 * y3 - z3 * z3) * grad_3d(g3, x3, y3, z3);
+
+        (n0 + n1 + n2 + n3) * SIMPLEX_3D_SCALE
+    }
+
+    // 1D value noise.
+    pub fn value_1d(&self, x: f32) -> f32 {
+        let xi = lattice(x);
+        let u = fade(x - x.floor());
+
+        // Rounding in the interpolation can overshoot the lattice values slightly.
+        lerp(u, value(self.perm[xi]), value(self.perm[xi + 1])).clamp(-1.0, 1.0)
+    }
+
+    // 2D value noise.
+    pub fn value_2d(&self, x: f32, y: f32) -> f32 {
+        let (xi, yi) = (lattice(x), lattice(y));
+        let u = fade(x - x.floor());
+        let v = fade(y - y.floor());
+
+        let a = self.hash(xi) + yi;
+        let b = self.hash(xi + 1) + yi;
+
+        lerp(
+            v,
+            lerp(u, value(self.perm[a]), value(self.perm[b])),
+            lerp(u, value(self.perm[a + 1]), value(self.perm[b + 1])),
+        )
+        .clamp(-1.0, 1.0)
+    }
+
+    // 3D value noise.
+    pub fn value_3d(&self, x: f32, y: f32, z: f32) -> f32 {
+        let (xi, yi, zi) = (lattice(x), lattice(y), lattice(z));
+        let u = fade(x - x.floor());
+        let v = fade(y - y.floor());
+        let w = fade(z - z.floor());
+
+        let a = self.hash(xi) + yi;
+        let aa = self.hash(a) + zi;
+        let ab = self.hash(a + 1) + zi;
+        let b = self.hash(xi + 1) + yi;
+        let ba = self.hash(b) + zi;
+        let bb = self.hash(b + 1) + zi;
+
+        lerp(
+            w,
+            lerp(
+                v,
+                lerp(u, value(self.perm[aa]), value(self.perm[ba])),
+                lerp(u, value(self.perm[ab]), value(self.perm[bb])),
+            ),
+            lerp(
+                v,
+                lerp(u, value(self.perm[aa + 1]), value(self.perm[ba + 1])),
+                lerp(u, value(self.perm[ab + 1]), value(self.perm[bb + 1])),
+            ),
+        )
+        .clamp(-1.0, 1.0)
+    }
+
+    // Permutation value as an index for the next lookup.
+    fn hash(&self, index: usize) -> usize {
+        usize::from(self.perm[index])
+    }
 }
 
 // Fade function for smooth interpolation.
@@ -43,8 +439,18 @@ fn fade(t: f32) -> f32 {
     t * t * t * t.mul_add(t.mul_add(6.0, -15.0), 10.0)
 }
 
+// Radial falloff of a simplex corner's contribution, zero outside its radius.
+fn falloff(t: f32) -> f32 {
+    if t < 0.0 { 0.0 } else { (t * t) * (t * t) }
+}
+
+// Compute gradient using hash value to pick a slope of 1 or -1.
+fn grad_1d(hash: u8, x: f32) -> f32 {
+    if (hash & 1) == 0 { x } else { -x }
+}
+
 // Compute gradient using hash value to select from 8 possible gradient directions.
-fn grad(hash: u8, x: f32, y: f32) -> f32 {
+fn grad_2d(hash: u8, x: f32, y: f32) -> f32 {
     let h = hash & 7;
     let u = if h < 4 { x } else { y };
     let v = if h < 4 { y } else { x };
@@ -55,38 +461,199 @@ fn grad(hash: u8, x: f32, y: f32) -> f32 {
     u_sign + v_sign
 }
 
+// Compute gradient using hash value to select from the 12 directions to the edges of a cube.
+fn grad_3d(hash: u8, x: f32, y: f32, z: f32) -> f32 {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 {
+        y
+    } else if h == 12 || h == 14 {
+        x
+    } else {
+        z
+    };
+
+    let u_sign = if (h & 1) == 0 { u } else { -u };
+    let v_sign = if (h & 2) == 0 { v } else { -v };
+
+    u_sign + v_sign
+}
+
+// Compute gradient using hash value to select a slope between -8 and 8.
+fn grad_simplex_1d(hash: u8, x: f32) -> f32 {
+    let slope = f32::from(1 + (hash & 7));
+    if (hash & 8) == 0 { slope * x } else { -slope * x }
+}
+
+// Lattice coordinate of the cell containing the position, wrapped to the permutation table.
+fn lattice(position: f32) -> usize {
+    (position.floor() as i32 & 255) as usize
+}
+
+// Combine octaves of noise sampled at increasing frequencies.
+fn layer(fractal: &Fractal, sample: impl Fn(f32) -> f32) -> f32 {
+    let mut total = 0.0;
+    let mut frequency = 1.0;
+    let mut amplitude = 1.0;
+    let mut max_value = 0.0;
+
+    for _ in 0..fractal.octaves {
+        let noise = sample(frequency);
+        let octave = match fractal.mode {
+            Mode::Fbm => noise,
+            Mode::Ridged => (1.0 - noise.abs()).powi(2),
+            Mode::Turbulence => noise.abs(),
+        };
+        total += octave * amplitude;
+
+        max_value += amplitude;
+        amplitude *= fractal.gain;
+        frequency *= fractal.lacunarity;
+    }
+
+    // Normalize to the range of a single octave.
+    if max_value > 0.0 { total / max_value } else { 0.0 }
+}
+
 // Linear interpolation.
 fn lerp(t: f32, a: f32, b: f32) -> f32 {
     t.mul_add(b - a, a)
 }
 
-// Generate 2D Perlin noise at the given coordinates, normalized in the range [-1, 1].
-fn perlin_2d(x: f32, y: f32) -> f32 {
-    // Relative position within the cell.
-    let x_rel = x - x.floor();
-    let y_rel = y - y.floor();
+// Random value in the range [-1, 1] for a lattice point.
+// NOTE: dividing by 127.5 rather than multiplying by its reciprocal keeps 255 at exactly 1.
+fn value(hash: u8) -> f32 {
+    f32::from(hash) / 127.5 - 1.0
+}
 
-    // Fade curves for smooth interpolation.
-    let u = fade(x_rel);
-    let v = fade(y_rel);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    // Hash coordinates of the 4 cube corners.
-    let a = f32::from(perm(x)) + y;
-    let b = f32::from(perm(x + 1.)) + y;
-    let aa = perm(a);
-    let ab = perm(a + 1.);
-    let ba = perm(b);
-    let bb = perm(b + 1.);
+    const BASES: [Basis; 3] = [Basis::Perlin, Basis::Simplex, Basis::Value];
 
-    // Blend results from 4 corners of the square.
-    lerp(
-        v,
-        lerp(u, grad(aa, x_rel, y_rel), grad(ba, x_rel - 1.0, y_rel)),
-        lerp(u, grad(ab, x_rel, y_rel - 1.0), grad(bb, x_rel - 1.0, y_rel - 1.0)),
-    )
-}
+    // Points spread over several cells, including negative coordinates and the table wrap around.
+    fn points() -> impl Iterator<Item = (f32, f32, f32)> {
+        (0..4000).map(|i| {
+            let i = i as f32;
+            ((i * 0.137) - 260.0, (i * 0.291).sin() * 40.0, (i * 0.053) - 100.0)
+        })
+    }
+
+    #[test]
+    fn samples_are_in_range() {
+        for seed in [0, 1, 42, u64::MAX] {
+            let noise = Noise::new(seed);
+            for basis in BASES {
+                for (x, y, z) in points() {
+                    for value in [
+                        noise.sample_1d(basis, x),
+                        noise.sample_2d(basis, x, y),
+                        noise.sample_3d(basis, x, y, z),
+                    ] {
+                        assert!(
+                            (-1.0..=1.0).contains(&value),
+                            "{basis:?} gave {value} at ({x}, {y}, {z})"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fractal_modes_are_in_range() {
+        let noise = Noise::new(7);
+        for basis in BASES {
+            for (mode, range) in [
+                (Mode::Fbm, -1.0..=1.0),
+                (Mode::Ridged, 0.0..=1.0),
+                (Mode::Turbulence, 0.0..=1.0),
+            ] {
+                let fractal = Fractal {
+                    basis,
+                    mode,
+                    octaves: 5,
+                    lacunarity: 2.3,
+                    gain: 0.6,
+                };
+                for (x, y, z) in points() {
+                    for value in [
+                        noise.fractal_1d(&fractal, x),
+                        noise.fractal_2d(&fractal, x, y),
+                        noise.fractal_3d(&fractal, x, y, z),
+                    ] {
+                        assert!(range.contains(&value), "{basis:?} {mode:?} gave {value}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn samples_are_continuous() {
+        const STEP: f32 = 0.001;
+        // Generous bound on the slope of any of the noise functions.
+        const MAX_SLOPE: f32 = 40.0;
+
+        let noise = Noise::new(3);
+        for basis in BASES {
+            for (x, y, z) in points() {
+                let pairs = [
+                    (noise.sample_1d(basis, x), noise.sample_1d(basis, x + STEP)),
+                    (noise.sample_2d(basis, x, y), noise.sample_2d(basis, x + STEP, y)),
+                    (noise.sample_2d(basis, x, y), noise.sample_2d(basis, x, y + STEP)),
+                    (noise.sample_3d(basis, x, y, z), noise.sample_3d(basis, x, y, z + STEP)),
+                ];
+                for (a, b) in pairs {
+                    assert!(
+                        (a - b).abs() < STEP * MAX_SLOPE,
+                        "{basis:?} jumped from {a} to {b} at ({x}, {y}, {z})"
+                    );
+                }
+            }
+        }
+    }
 
-// Get permutation value with wrapping
-const fn perm(index: f32) -> u8 {
-    PERMUTATION[(index as usize) & 255]
+    #[test]
+    fn lattice_values_reach_the_ends_of_the_range() {
+        assert_eq!(value(0), -1.0);
+        assert_eq!(value(255), 1.0);
+    }
+
+    #[test]
+    fn perlin_is_zero_on_the_lattice() {
+        let noise = Noise::new(11);
+        for i in -20..20 {
+            let i = i as f32;
+            assert_eq!(noise.perlin_1d(i), 0.0);
+            assert_eq!(noise.perlin_2d(i, i * 3.0), 0.0);
+            assert_eq!(noise.perlin_3d(i, -i, i * 2.0), 0.0);
+        }
+    }
+
+    #[test]
+    fn seeds_are_deterministic_and_distinct() {
+        let a = Noise::new(42);
+        let b = Noise::new(42);
+        let c = Noise::new(43);
+
+        let same = points().all(|(x, y, _)| a.simplex_2d(x, y) == b.simplex_2d(x, y));
+        let different = points()
+            .filter(|&(x, y, _)| a.simplex_2d(x, y) != c.simplex_2d(x, y))
+            .count();
+        assert!(same);
+        assert!(different > 3000);
+    }
+
+    #[test]
+    fn seeded_tables_are_permutations() {
+        let noise = Noise::new(99);
+        let mut seen = [false; 256];
+        for &value in &noise.perm[..256] {
+            seen[usize::from(value)] = true;
+        }
+        assert!(seen.iter().all(|&seen| seen));
+        assert_eq!(noise.perm[..256], noise.perm[256..]);
+    }
 }
@@ -0,0 +1,324 @@
+use bevy::prelude::*;
+use rand::{Rng, rngs::ThreadRng};
+use std::f32::consts::TAU;
+
+use crate::{
+    accessibility::{AccessibilitySettings, REDUCED_PARTICLE_SPEED},
+    app::CardState,
+    noise::{Basis, Fractal, Mode, Noise},
+};
+
+// Emitter spawning sprite particles around it, e.g. embers over a fire or snow over the whole card.
+#[derive(Component)]
+pub struct Emitter {
+    // Particles a second at full strength, the strength in the range [0, 1] scales it down.
+    rate: f32,
+    pub strength: f32,
+    shape: Shape,
+    // Range of seconds the particles last and their starting velocities.
+    lifetime: (f32, f32),
+    velocity: (Vec2, Vec2),
+    // Downward acceleration, negative for particles that rise, and how much the wind carries them.
+    gravity: f32,
+    wind: f32,
+    // Fastest sideways sway, each particle sways at its own speed up to it.
+    wobble: f32,
+    // Size and color at the start and end of a particle's life, the color tinted by one of the tints picked at random.
+    size: (f32, f32),
+    color: (Color, Color),
+    tints: Vec<Color>,
+    // Area of the world particles are gone once they leave, no matter how long they have left.
+    bounds: Option<Rect>,
+    // Seconds the emitter has already been running for when it's spawned, so it starts out full of particles.
+    prewarm: f32,
+    // Particles owed from earlier frames and bursts, only whole particles are spawned.
+    pending: f32,
+}
+
+// Area around an emitter that particles spawn in.
+#[derive(Clone, Copy)]
+pub enum Shape {
+    Point,
+    // Anywhere in a box this far out from the emitter.
+    Box(Vec2),
+    // Anywhere in a circle of this radius.
+    Circle(f32),
+}
+
+// Particle moving and changing over its life.
+// NOTE: particles that are done are hidden and kept for reuse rather than despawned.
+#[derive(Component)]
+struct Particle {
+    velocity: Vec2,
+    age: f32,
+    lifetime: f32,
+    gravity: f32,
+    wind: f32,
+    // Sideways sway speed and where in the sway the particle started.
+    wobble: f32,
+    phase: f32,
+    size: (f32, f32),
+    color: (LinearRgba, LinearRgba),
+    bounds: Option<Rect>,
+}
+
+// Particles that are done and can be reused.
+#[derive(Resource, Default)]
+struct ParticlePool(Vec<Entity>);
+
+// Wind blowing across the card in units a second, positive to the right, gusting over time.
+#[derive(Resource, Default)]
+pub struct Wind(pub f32);
+
+// Wind blows to the left, gusting up to the strongest and occasionally dropping to nothing.
+const WIND_GUST: f32 = -8.0;
+const WIND_FREQUENCY: f32 = 1.0 / 20.0;
+static WIND_NOISE: Noise = Noise::new(7);
+const WIND_FRACTAL: Fractal = Fractal {
+    basis: Basis::Value,
+    mode: Mode::Fbm,
+    octaves: 2,
+    lacunarity: 2.0,
+    gain: 0.5,
+};
+
+impl Emitter {
+    pub const fn new(rate: f32, shape: Shape, lifetime: (f32, f32), velocity: (Vec2, Vec2)) -> Self {
+        Self {
+            rate,
+            strength: 0.0,
+            shape,
+            lifetime,
+            velocity,
+            gravity: 0.0,
+            wind: 0.0,
+            wobble: 0.0,
+            size: (1.0, 1.0),
+            color: (Color::WHITE, Color::WHITE),
+            tints: Vec::new(),
+            bounds: None,
+            prewarm: 0.0,
+            pending: 0.0,
+        }
+    }
+
+    pub const fn with_strength(mut self, strength: f32) -> Self {
+        self.strength = strength;
+        self
+    }
+
+    pub const fn with_gravity(mut self, gravity: f32) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    pub const fn with_wind(mut self, wind: f32) -> Self {
+        self.wind = wind;
+        self
+    }
+
+    pub const fn with_wobble(mut self, wobble: f32) -> Self {
+        self.wobble = wobble;
+        self
+    }
+
+    pub const fn with_size(mut self, start: f32, end: f32) -> Self {
+        self.size = (start, end);
+        self
+    }
+
+    pub const fn with_color(mut self, start: Color, end: Color) -> Self {
+        self.color = (start, end);
+        self
+    }
+
+    pub fn with_tints(mut self, tints: &[Color]) -> Self {
+        self.tints = tints.to_vec();
+        self
+    }
+
+    pub const fn with_bounds(mut self, bounds: Rect) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    pub const fn with_prewarm(mut self, seconds: f32) -> Self {
+        self.prewarm = seconds;
+        self
+    }
+
+    // Spawn a number of particles at once on top of the emitter's rate, e.g. confetti.
+    pub fn burst(&mut self, count: u32) {
+        self.pending += count as f32;
+    }
+
+    // A new particle with a random place in the emitter's shape, velocity, lifetime, sway and tint.
+    fn particle(&self, rng: &mut ThreadRng, origin: Vec2) -> (Vec2, Particle) {
+        let offset = match self.shape {
+            Shape::Point => Vec2::ZERO,
+            Shape::Box(size) => Vec2::new(rng.random_range(-size.x..=size.x), rng.random_range(-size.y..=size.y)),
+            // Square root of the distance keeps the particles from bunching up in the middle.
+            Shape::Circle(radius) => {
+                Vec2::from_angle(rng.random_range(0.0..TAU)) * radius * rng.random_range(0.0_f32..=1.0).sqrt()
+            }
+        };
+        let (min, max) = self.velocity;
+        let tint = if self.tints.is_empty() {
+            Vec4::ONE
+        } else {
+            self.tints[rng.random_range(0..self.tints.len())].to_linear().to_vec4()
+        };
+
+        let particle = Particle {
+            velocity: Vec2::new(rng.random_range(min.x..=max.x), rng.random_range(min.y..=max.y)),
+            age: 0.0,
+            lifetime: rng.random_range(self.lifetime.0..=self.lifetime.1),
+            gravity: self.gravity,
+            wind: self.wind,
+            wobble: rng.random_range(-self.wobble..=self.wobble),
+            phase: rng.random_range(0.0..TAU),
+            size: self.size,
+            color: (tinted(self.color.0, tint), tinted(self.color.1, tint)),
+            bounds: self.bounds,
+        };
+
+        (origin + offset, particle)
+    }
+}
+
+impl Particle {
+    // Move the particle on and age it, false once it's done.
+    fn update(&mut self, translation: &mut Vec3, wind: f32, delta: f32) -> bool {
+        self.age += delta;
+        self.velocity.y -= self.gravity * delta;
+
+        let sway = self.wobble * (self.age + self.phase).sin();
+        let velocity = self.velocity + Vec2::X * wind.mul_add(self.wind, sway);
+        *translation += (velocity * delta).extend(0.0);
+
+        self.age < self.lifetime && self.bounds.is_none_or(|bounds| bounds.contains(translation.truncate()))
+    }
+
+    // Size and color for how far through its life the particle is.
+    fn draw(&self, sprite: &mut Sprite) {
+        let life = self.age / self.lifetime;
+        sprite.custom_size = Some(Vec2::splat(self.size.0.lerp(self.size.1, life)));
+        sprite.color = self.color.0.mix(&self.color.1, life).into();
+    }
+}
+
+// Add the particle systems.
+pub fn add_systems(app: &mut App) {
+    app.init_resource::<Wind>()
+        .init_resource::<ParticlePool>()
+        .add_systems(OnExit(CardState::Running), clear_pool)
+        .add_systems(Update, (handle_wind, handle_emitters, handle_particles).chain());
+}
+
+// Color multiplied by a tint, channel by channel.
+fn tinted(color: Color, tint: Vec4) -> LinearRgba {
+    LinearRgba::from_vec4(color.to_linear().to_vec4() * tint)
+}
+
+// Forget the pooled particles, they're despawned with the rest of the card.
+fn clear_pool(mut pool: ResMut<ParticlePool>) {
+    pool.0.clear();
+}
+
+// Gust the wind with slow noise.
+fn handle_wind(time: Res<Time>, mut wind: ResMut<Wind>) {
+    let noise = WIND_NOISE.fractal_1d(&WIND_FRACTAL, time.elapsed_secs() * WIND_FREQUENCY);
+    wind.0 = WIND_GUST * noise.mul_add(0.75, 0.5).clamp(0.0, 1.0);
+}
+
+// Spawn the particles each emitter is owed, reusing pooled particles before spawning new ones.
+// NOTE: reduced motion plays the particles in slow motion, emitters slow down with them so there are as many about.
+fn handle_emitters(
+    mut commands: Commands,
+    time: Res<Time>,
+    accessibility: Res<AccessibilitySettings>,
+    wind: Res<Wind>,
+    mut pool: ResMut<ParticlePool>,
+    mut emitters: Query<(&mut Emitter, &GlobalTransform)>,
+    mut particles: Query<(&mut Particle, &mut Transform, &mut Sprite, &mut Visibility)>,
+) {
+    let mut rng = rand::rng();
+    let speed = if accessibility.reduced_motion {
+        REDUCED_PARTICLE_SPEED
+    } else {
+        1.0
+    };
+
+    for (mut emitter, transform) in &mut emitters {
+        let origin = transform.translation();
+        emitter.pending += emitter.rate * emitter.strength * speed * time.delta_secs();
+
+        // Fill a new emitter with particles part way through their lives.
+        let prewarm = std::mem::take(&mut emitter.prewarm);
+        let mut aged = (emitter.rate * emitter.strength * prewarm) as u32;
+
+        while emitter.pending >= 1.0 || aged > 0 {
+            let (position, mut particle) = emitter.particle(&mut rng, origin.truncate());
+            let mut translation = position.extend(origin.z);
+
+            if aged > 0 {
+                aged -= 1;
+                if !particle.update(&mut translation, wind.0, rng.random_range(0.0..=prewarm)) {
+                    continue;
+                }
+            } else {
+                emitter.pending -= 1.0;
+            }
+
+            // Reuse a pooled particle, skipping any that have been despawned.
+            let pooled = std::iter::from_fn(|| pool.0.pop()).find(|entity| particles.contains(*entity));
+            if let Some(entity) = pooled
+                && let Ok((mut pooled, mut transform, mut sprite, mut visibility)) = particles.get_mut(entity)
+            {
+                transform.translation = translation;
+                particle.draw(&mut sprite);
+                *pooled = particle;
+                *visibility = Visibility::Inherited;
+                continue;
+            }
+
+            let mut sprite = Sprite::default();
+            particle.draw(&mut sprite);
+            commands.spawn((
+                sprite,
+                Transform::from_translation(translation),
+                DespawnOnExit(CardState::Running),
+                particle,
+            ));
+        }
+    }
+}
+
+// Move the particles with the wind, changing their size and color over their life and pooling them when they're done.
+fn handle_particles(
+    time: Res<Time>,
+    accessibility: Res<AccessibilitySettings>,
+    wind: Res<Wind>,
+    mut pool: ResMut<ParticlePool>,
+    mut query: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite, &mut Visibility)>,
+) {
+    let speed = if accessibility.reduced_motion {
+        REDUCED_PARTICLE_SPEED
+    } else {
+        1.0
+    };
+    let delta = time.delta_secs() * speed;
+
+    for (entity, mut particle, mut transform, mut sprite, mut visibility) in &mut query {
+        if *visibility == Visibility::Hidden {
+            continue;
+        }
+
+        if particle.update(&mut transform.translation, wind.0, delta) {
+            particle.draw(&mut sprite);
+        } else {
+            *visibility = Visibility::Hidden;
+            pool.0.push(entity);
+        }
+    }
+}
@@ -0,0 +1,314 @@
+use bevy::prelude::*;
+use bevy_light_2d::prelude::*;
+
+use crate::{
+    app::CardState,
+    camera::CameraBounds,
+    interaction::{self, Highlight, Hovered, Interactable, InteractionEvent},
+    theman::TheMan,
+};
+
+// Rooms of the house the man can be in.
+// NOTE: the rooms are areas of the house cross-section, each with its own props, lights, walls and ambience. They all
+// stay spawned so everything keeps its state while the man is somewhere else.
+#[derive(States, Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Room {
+    #[default]
+    Living,
+    Attic,
+    Porch,
+}
+
+// Props, lights and walls belonging to a room.
+#[derive(Component, Clone, Copy)]
+pub struct InRoom(pub Room);
+
+// Added to a room's contents while the man is in another room, they can't be pointed at or clicked from there.
+#[derive(Component)]
+pub struct Away;
+
+// Interactable that takes the man between two rooms, arriving at the given x in each.
+#[derive(Component)]
+struct Door {
+    rooms: [(Room, f32); 2],
+}
+
+// Fade to black and back, moving the man into the next room while the screen is dark.
+#[derive(Component)]
+pub struct Fade {
+    room: Room,
+    x: f32,
+    timer: Timer,
+}
+
+const FADE_SECONDS: f32 = 0.6;
+
+// Ceiling between the living room and the attic, and the front wall of the house behind the porch.
+const CEILING: Rect = Rect {
+    min: Vec2::new(-93.0, 8.0),
+    max: Vec2::new(173.0, 12.0),
+};
+const FRONT_WALL: Rect = Rect {
+    min: Vec2::new(-94.0, -80.0),
+    max: Vec2::new(-86.0, 10.0),
+};
+
+impl Room {
+    // Scene the camera frames and is kept to while in the room, from the ground to the sky of the house cross-section.
+    // NOTE: the porch takes in the front of the living room so it's never narrower than the camera follows at.
+    pub fn bounds(self) -> Rect {
+        match self {
+            Self::Living => Rect::new(-150.0, -75.0, 150.0, 75.0),
+            Self::Attic => Rect::new(-40.0, -75.0, 150.0, 75.0),
+            Self::Porch => Rect::new(-150.0, -75.0, -30.0, 75.0),
+        }
+    }
+
+    // Part of the house cross-section the room takes up.
+    fn area(self) -> Rect {
+        match self {
+            Self::Living => Rect::new(-94.0, -75.0, 150.0, 9.0),
+            Self::Attic => Rect::new(-40.0, 9.0, 150.0, 75.0),
+            Self::Porch => Rect::new(-150.0, -75.0, -90.0, 0.0),
+        }
+    }
+
+    // Left and right limits of where the man can walk.
+    pub const fn walk_range(self) -> (f32, f32) {
+        match self {
+            Self::Living => (-82.0, 160.0),
+            Self::Attic => (-30.0, 145.0),
+            Self::Porch => (-145.0, -100.0),
+        }
+    }
+
+    // Height of the man's center when standing on the floor.
+    pub const fn floor(self) -> f32 {
+        match self {
+            Self::Living | Self::Porch => -56.0,
+            Self::Attic => 30.0,
+        }
+    }
+
+    // Color and intensity of the light filling the room, the attic is dim and outside is lit by the moon.
+    pub const fn ambience(self) -> (Color, f32) {
+        match self {
+            Self::Living => (Color::srgb(1.0, 0.9, 0.75), 0.04),
+            Self::Attic => (Color::srgb(1.0, 0.8, 0.6), 0.02),
+            Self::Porch => (Color::srgb(0.7, 0.8, 1.0), 0.07),
+        }
+    }
+
+    // Walls keeping the room's light in, the attic's sloped roof is built separately.
+    const fn walls(self) -> &'static [Rect] {
+        match self {
+            Self::Living => &[CEILING],
+            Self::Attic => &[],
+            Self::Porch => &[FRONT_WALL],
+        }
+    }
+}
+
+// Add the room systems.
+pub fn add_systems(app: &mut App) {
+    app.init_state::<Room>()
+        .add_systems(OnEnter(CardState::Running), init)
+        .add_systems(
+            Update,
+            (handle_doors, handle_fade, handle_bounds, handle_contents).chain(),
+        );
+}
+
+// Mark the contents of every room but the current one as away, no longer pointed at if they were.
+fn handle_contents(mut commands: Commands, room: Res<State<Room>>, query: Query<(Entity, Ref<InRoom>, Has<Away>)>) {
+    for (entity, in_room, away) in &query {
+        if !room.is_changed() && !in_room.is_changed() {
+            continue;
+        }
+
+        match (in_room.0 == *room.get(), away) {
+            (true, true) => {
+                commands.entity(entity).remove::<Away>();
+            }
+            (false, false) => {
+                commands.entity(entity).insert(Away).remove::<(Hovered, Highlight)>();
+            }
+            _ => {}
+        }
+    }
+}
+
+// Keep the camera inside the current room.
+fn handle_bounds(room: Res<State<Room>>, mut bounds: ResMut<CameraBounds>) {
+    if room.is_changed() {
+        bounds.0 = room.get().bounds();
+    }
+}
+
+// Start fading into the room on the other side of a door.
+fn handle_doors(
+    mut commands: Commands,
+    room: Res<State<Room>>,
+    mut events: MessageReader<InteractionEvent>,
+    doors: Query<(&Door, &Interactable)>,
+    fades: Query<(), With<Fade>>,
+) {
+    // Ignore doors while already going through one.
+    if !fades.is_empty() {
+        events.clear();
+        return;
+    }
+
+    let next = events.read().find_map(|event| {
+        let (door, _) = doors.iter().find(|(_, interactable)| interactable.id == event.id)?;
+        door.rooms.iter().find(|(next, _)| next != room.get()).copied()
+    });
+
+    if let Some((next, x)) = next {
+        commands.spawn((
+            Fade {
+                room: next,
+                x,
+                timer: Timer::from_seconds(FADE_SECONDS, TimerMode::Once),
+            },
+            Node {
+                position_type: PositionType::Absolute,
+                width: percent(100),
+                height: percent(100),
+                ..default()
+            },
+            BackgroundColor(Color::NONE),
+            GlobalZIndex(20),
+            DespawnOnExit(CardState::Running),
+        ));
+    }
+}
+
+// Fade out, move the man into the next room halfway through and fade back in.
+fn handle_fade(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut next_room: ResMut<NextState<Room>>,
+    mut fades: Query<(Entity, &mut Fade, &mut BackgroundColor)>,
+    mut man: Option<Single<&mut Transform, With<TheMan>>>,
+) {
+    for (entity, mut fade, mut color) in &mut fades {
+        let halfway = fade.timer.elapsed_secs() >= FADE_SECONDS / 2.0;
+        fade.timer.tick(time.delta());
+
+        if !halfway && fade.timer.elapsed_secs() >= FADE_SECONDS / 2.0 {
+            next_room.set(fade.room);
+            if let Some(man) = &mut man {
+                man.translation.x = fade.x;
+                man.translation.y = fade.room.floor();
+            }
+        }
+
+        let alpha = 1.0 - (fade.timer.fraction() * 2.0 - 1.0).abs();
+        color.0 = Color::BLACK.with_alpha(alpha);
+
+        if fade.timer.is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+// Room initialization.
+fn init(mut commands: Commands, asset_server: Res<AssetServer>, mut next_room: ResMut<NextState<Room>>) {
+    next_room.set(Room::Living);
+
+    // Light filling each room and the walls keeping it in.
+    for room in [Room::Living, Room::Attic, Room::Porch] {
+        let area = room.area();
+        let (color, intensity) = room.ambience();
+        commands.spawn((
+            InRoom(room),
+            Transform::from_translation(area.center().extend(5.0)),
+            DespawnOnExit(CardState::Running),
+            PointLight2d {
+                color,
+                intensity,
+                radius: area.half_size().length() * 1.5,
+                cast_shadows: true,
+                ..default()
+            },
+        ));
+
+        for wall in room.walls() {
+            commands.spawn((
+                InRoom(room),
+                Transform::from_translation(wall.center().extend(2.0)),
+                DespawnOnExit(CardState::Running),
+                LightOccluder2d {
+                    shape: LightOccluder2dShape::Rectangle {
+                        half_size: wall.half_size(),
+                    },
+                },
+            ));
+        }
+    }
+
+    // Build the attic's sloped roof from small pieces due to a bug where LightOccluder2d ignores transformations.
+    let x_offset: f32 = -94.0;
+    let y_offset: f32 = 12.0;
+    let slope: f32 = 1.28;
+    for point in 0..92i16 {
+        let x = f32::from(point).mul_add(slope, x_offset);
+        let y = (f32::from(point) / slope) + y_offset;
+
+        commands.spawn((
+            InRoom(Room::Attic),
+            Transform::from_xyz(x, y, 2.0),
+            DespawnOnExit(CardState::Running),
+            LightOccluder2d {
+                shape: LightOccluder2dShape::Rectangle {
+                    half_size: Vec2::new(1.0, 1.0),
+                },
+            },
+        ));
+    }
+
+    // Front door in the left wall between the living room and the porch.
+    commands.spawn((
+        Sprite {
+            image: asset_server.load("house/door.png"),
+            ..default()
+        },
+        Transform::from_xyz(-90.0, -60.0, 3.0),
+        DespawnOnExit(CardState::Running),
+        Door {
+            rooms: [(Room::Living, -80.0), (Room::Porch, -102.0)],
+        },
+        interaction::State::Off,
+        Interactable {
+            id: "front-door".to_string(),
+            height: 24.0,
+            width: 12.0,
+            sprite_height: 24.0,
+            sprite_width: 12.0,
+            ..default()
+        },
+    ));
+
+    // Ladder from the living room up through the ceiling into the attic.
+    commands.spawn((
+        Sprite {
+            image: asset_server.load("house/ladder.png"),
+            ..default()
+        },
+        Transform::from_xyz(52.0, -29.0, 3.0),
+        DespawnOnExit(CardState::Running),
+        Door {
+            rooms: [(Room::Living, 52.0), (Room::Attic, 52.0)],
+        },
+        interaction::State::Off,
+        Interactable {
+            id: "attic-ladder".to_string(),
+            height: 90.0,
+            width: 4.0,
+            sprite_height: 86.0,
+            sprite_width: 10.0,
+            ..default()
+        },
+    ));
+}
@@ -1,134 +1,228 @@
-use std::time::Duration;
-
-use bevy::prelude::*;
+use bevy::{ecs::system::SystemParam, platform::collections::HashMap, prelude::*, time::Stopwatch};
+use serde::Deserialize;
 
 use crate::{
-    animation::AnimationConfig,
+    app::CardState,
+    data,
+    fireplace::Fireplace,
+    interaction::{Interactable, State},
+    theman::{Sitting, TheMan},
+    timeline::{Actor, PlayTimelineEvent, Timeline, TimelineEvent},
     tree::{Presents, Tree},
+    warmth::Warmth,
 };
 
 #[derive(Clone, Resource)]
-struct SpriteAssets {
-    animation_sprite: Handle<Image>,
-    animation_layout: Handle<TextureAtlasLayout>,
+struct VisitAssets {
+    chimney_visit: Handle<Timeline>,
+    door_visit: Handle<Timeline>,
+    peek_visit: Handle<Timeline>,
+    rules: Handle<VisitRules>,
 }
 
 #[derive(Component)]
 pub struct Santa;
 
-#[derive(Component)]
-pub struct Run;
+// What santa leaves under the tree.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub enum Gift {
+    #[default]
+    Presents,
+    Extra,
+    Coal,
+}
+
+// How santa's visit goes.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+enum Visit {
+    // Peek in and leave without leaving anything.
+    Peek,
+    // Come in and leave a gift under the tree.
+    Gift(Gift),
+}
+
+// A visit picked when all of the rule's conditions match the scene.
+#[derive(Deserialize)]
+struct VisitRule {
+    // Interactable ids that need to be on (true) or off (false).
+    #[serde(default)]
+    states: HashMap<String, bool>,
+    #[serde(default)]
+    presents: Option<bool>,
+    #[serde(default)]
+    sitting: Option<bool>,
+    // Time spent in the card, in seconds.
+    #[serde(default)]
+    min_seconds: Option<f32>,
+    #[serde(default)]
+    max_seconds: Option<f32>,
+    // How warm the man is, from 0 when he's freezing to 1 when he's toasty.
+    #[serde(default)]
+    min_warmth: Option<f32>,
+    #[serde(default)]
+    max_warmth: Option<f32>,
+    visit: Visit,
+}
+
+// Santa's visit rules, checked in order with the first match picking the visit.
+#[derive(Asset, TypePath, Deserialize)]
+#[serde(transparent)]
+struct VisitRules(Vec<VisitRule>);
+
+// The parts of the scene santa's visit rules can check.
+struct SceneState {
+    states: HashMap<String, bool>,
+    presents: bool,
+    sitting: bool,
+    seconds: f32,
+    warmth: f32,
+}
+
+// Queries for reading the scene state.
+#[derive(SystemParam)]
+struct Scene<'w, 's> {
+    clock: Res<'w, CardClock>,
+    warmth: Res<'w, Warmth>,
+    interactables: Query<'w, 's, (&'static Interactable, &'static State)>,
+    fireplace: Query<'w, 's, &'static State, With<Fireplace>>,
+    sitting: Query<'w, 's, (), (With<TheMan>, With<Sitting>)>,
+    tree: Query<'w, 's, &'static Children, With<Tree>>,
+    presents: Query<'w, 's, (), With<Presents>>,
+}
+
+// Time spent in the card since it was started or reset.
+#[derive(Resource, Default)]
+struct CardClock(Stopwatch);
+
+// The gift santa is bringing on the current visit.
+#[derive(Resource, Default)]
+struct CurrentGift(Gift);
 
 #[derive(Message)]
-pub struct AddPresentsEvent;
+pub struct AddPresentsEvent(pub Gift);
 
 #[derive(Message)]
 pub struct SantasHereEvent;
 
+// Timeline event for santa leaving the presents under the tree.
+const ADD_PRESENTS: &str = "add-presents";
+
+impl Scene<'_, '_> {
+    fn state(&self) -> SceneState {
+        SceneState {
+            states: self
+                .interactables
+                .iter()
+                .map(|(interactable, state)| (interactable.id.clone(), *state == State::On))
+                .collect(),
+            presents: self
+                .tree
+                .iter()
+                .flat_map(|children| children.iter())
+                .any(|child| self.presents.contains(child)),
+            sitting: !self.sitting.is_empty(),
+            seconds: self.clock.0.elapsed_secs(),
+            warmth: self.warmth.0,
+        }
+    }
+
+    fn fire_lit(&self) -> bool {
+        self.fireplace.iter().any(|state| *state == State::On)
+    }
+}
+
+impl VisitRule {
+    fn matches(&self, scene: &SceneState) -> bool {
+        self.states
+            .iter()
+            .all(|(id, on)| scene.states.get(id).is_some_and(|state| state == on))
+            && self.presents.is_none_or(|presents| presents == scene.presents)
+            && self.sitting.is_none_or(|sitting| sitting == scene.sitting)
+            && self.min_seconds.is_none_or(|min| scene.seconds >= min)
+            && self.max_seconds.is_none_or(|max| scene.seconds < max)
+            && self.min_warmth.is_none_or(|min| scene.warmth >= min)
+            && self.max_warmth.is_none_or(|max| scene.warmth < max)
+    }
+}
+
 // Add the animation systems.
 pub fn add_systems(app: &mut App) {
+    data::register::<VisitRules>(app, &["rules.ron"]);
+
     app.add_message::<AddPresentsEvent>()
         .add_message::<SantasHereEvent>()
-        .add_systems(Startup, init)
-        .add_systems(Update, (handle_animations, handle_start));
+        .init_resource::<CardClock>()
+        .init_resource::<CurrentGift>()
+        .add_systems(OnEnter(CardState::Running), init)
+        .add_systems(Update, (handle_clock, handle_start, handle_timeline_events));
 }
 
-// Advance animation frames and states.
-fn handle_animations(
-    mut commands: Commands,
-    time: Res<Time>,
-    mut presents_events: MessageWriter<AddPresentsEvent>,
-    mut query: Query<(Entity, &mut AnimationConfig, &mut Sprite), (With<Santa>, With<Run>)>,
-) {
-    for (entity, mut config, mut sprite) in &mut query {
-        config.frame_timer.tick(time.delta());
-
-        let mut finished = false;
-        let mut add_presents = false;
-        if config.frame_timer.just_finished()
-            && let Some(atlas) = &mut sprite.texture_atlas
-        {
-            // Animation plays once and is removed.
-            if atlas.index < config.last_index {
-                atlas.index += 1;
-            } else {
-                if atlas.index == 27 {
-                    add_presents = true;
-                }
-                finished = true;
-            }
-
-            // Custom frame timing.
-            // NOTE: This is instead of copying redundant frames in the spritesheet.
-            config.frame_timer = match atlas.index {
-                2 => Timer::new(Duration::from_millis(500), TimerMode::Once),
-                3 => Timer::new(Duration::from_millis(750), TimerMode::Once),
-                7 | 12 => Timer::new(Duration::from_secs(2), TimerMode::Once),
-                19..24 => Timer::new(Duration::from_millis(350), TimerMode::Once),
-                27 => Timer::new(Duration::from_millis(250), TimerMode::Once),
-                _ => AnimationConfig::timer_from_fps(config.fps),
-            };
-        }
-
-        if finished {
-            *sprite = Sprite::default();
-            commands.entity(entity).remove::<Run>();
-            commands.entity(entity).remove::<Sprite>();
-            if add_presents {
-                presents_events.write(AddPresentsEvent);
-            }
-        }
-    }
+// Track the time spent in the card.
+fn handle_clock(time: Res<Time>, mut clock: ResMut<CardClock>) {
+    clock.0.tick(time.delta());
 }
 
+// Pick santa's visit from the rules and play it.
+// NOTE: santa won't come down a burning chimney so he uses the front door when the fire is lit.
 fn handle_start(
-    mut commands: Commands,
-    sprite_assets: Res<SpriteAssets>,
+    assets: Res<VisitAssets>,
+    rules: Res<Assets<VisitRules>>,
+    scene: Scene,
     mut events: MessageReader<SantasHereEvent>,
-    mut query: Query<(Entity, &mut AnimationConfig), With<Santa>>,
-    tree_query: Query<&Children, With<Tree>>,
-    presents_query: Query<Entity, With<Presents>>,
+    mut timeline_events: MessageWriter<PlayTimelineEvent>,
+    mut gift: ResMut<CurrentGift>,
 ) {
     for _event in events.read() {
-        for (entity, mut config) in &mut query {
-            let has_presents = tree_query
-                .iter()
-                .flat_map(|children| children.iter())
-                .any(|child| presents_query.contains(child));
-
-            if has_presents {
-                *config = AnimationConfig::new(0, 7, 6);
-            } else {
-                *config = AnimationConfig::new(0, 27, 6);
-            }
-            commands.entity(entity).insert(Sprite {
-                image: sprite_assets.animation_sprite.clone(),
-                texture_atlas: Some(TextureAtlas {
-                    layout: sprite_assets.animation_layout.clone(),
-                    index: 0,
-                }),
-                ..default()
-            });
-            commands.entity(entity).insert(Run);
+        let state = scene.state();
+
+        // Bring the usual presents if the rules haven't loaded.
+        let visit = rules
+            .get(&assets.rules)
+            .and_then(|rules| rules.0.iter().find(|rule| rule.matches(&state)))
+            .map_or(Visit::Gift(Gift::Presents), |rule| rule.visit);
+
+        let timeline = match visit {
+            Visit::Peek => &assets.peek_visit,
+            Visit::Gift(_) if scene.fire_lit() => &assets.door_visit,
+            Visit::Gift(_) => &assets.chimney_visit,
+        };
+        if let Visit::Gift(visit_gift) = visit {
+            gift.0 = visit_gift;
         }
+        timeline_events.write(PlayTimelineEvent(timeline.clone()));
     }
 }
 
-// Initialize the santa animation sprite sheet.
-fn init(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut texture_layouts: ResMut<Assets<TextureAtlasLayout>>,
+// Leave the gift when the visit timeline gets to it.
+fn handle_timeline_events(
+    gift: Res<CurrentGift>,
+    mut events: MessageReader<TimelineEvent>,
+    mut presents_events: MessageWriter<AddPresentsEvent>,
 ) {
-    let sprites = SpriteAssets {
-        animation_sprite: asset_server.load("santa/santa_animation.png"),
-        animation_layout: texture_layouts.add(TextureAtlasLayout::from_grid(UVec2::splat(32), 28, 1, None, None)),
-    };
-    commands.insert_resource(sprites);
+    for event in events.read() {
+        if event.0 == ADD_PRESENTS {
+            presents_events.write(AddPresentsEvent(gift.0));
+        }
+    }
+}
+
+// Initialize santa, hidden until his visit timeline shows him.
+// NOTE: santa is behind the fireplace and tree so he can climb down the chimney and reach under the tree.
+fn init(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(VisitAssets {
+        chimney_visit: asset_server.load("santa/chimney_visit.timeline.ron"),
+        door_visit: asset_server.load("santa/door_visit.timeline.ron"),
+        peek_visit: asset_server.load("santa/peek_visit.timeline.ron"),
+        rules: asset_server.load("santa/visit.rules.ron"),
+    });
+    commands.insert_resource(CardClock::default());
 
     commands.spawn((
-        Transform::from_translation(Vec3::new(-35.0, -56.0, 10.0)),
+        Sprite::default(),
+        Transform::from_translation(Vec3::new(-35.0, -56.0, 4.5)),
+        Visibility::Hidden,
+        DespawnOnExit(CardState::Running),
         Santa,
-        AnimationConfig::new(0, 27, 4),
+        Actor("santa"),
     ));
 }
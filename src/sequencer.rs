@@ -0,0 +1,235 @@
+use bevy::{ecs::system::SystemParam, prelude::*, time::Stopwatch};
+use bevy_light_2d::prelude::*;
+use serde::Deserialize;
+use std::f32::consts::TAU;
+
+use crate::{
+    accessibility::{AccessibilitySettings, REDUCED_FLICKER_SPEED},
+    data,
+    flickering_light::{FlickeringLight, LightFlickerSet},
+    noise::{Basis, Fractal, Mode, Noise},
+};
+
+// Light in an ordered string of lights that patterns play across.
+#[derive(Component)]
+pub struct SequencedLight {
+    // Position along the string and the color group it belongs to, e.g. every third light is red.
+    pub index: usize,
+    pub group: usize,
+}
+
+// Patterns the lights step through, authored in "lights/card.patterns.ron".
+#[derive(Asset, TypePath, Deserialize)]
+#[serde(transparent)]
+struct Patterns(Vec<Pattern>);
+
+// How bright each light along the string is over time, on top of its flicker.
+#[derive(Deserialize)]
+enum Pattern {
+    // Every light on.
+    Steady,
+    // Runs of `length` lit lights with `gap` dark ones between, moving `speed` lights a second.
+    Chase { length: f32, gap: f32, speed: f32 },
+    // Lights fading in and out on their own, higher sharpness keeps them dark for longer.
+    Twinkle { speed: f32, sharpness: f32 },
+    // One color group lit at a time, taking turns every period.
+    Alternate { period: f32 },
+    // Brightness rolling along the string, `wavelength` lights long and passing every period.
+    Wave { wavelength: f32, period: f32 },
+    // The whole string blinking out a message, a dot lasts one unit of seconds.
+    Morse { message: String, unit: f32 },
+}
+
+#[derive(Resource)]
+struct SequencerAssets {
+    patterns: Handle<Patterns>,
+}
+
+// Pattern that's playing and how long it's been playing for.
+#[derive(Resource, Default)]
+struct Sequence {
+    pattern: usize,
+    time: Stopwatch,
+}
+
+// Start and step through the patterns.
+#[derive(SystemParam)]
+pub struct Sequencer<'w> {
+    assets: Res<'w, SequencerAssets>,
+    patterns: Res<'w, Assets<Patterns>>,
+    sequence: ResMut<'w, Sequence>,
+}
+
+// Twinkling lights flash up sharply where the noise crests, each light's noise a row apart.
+static TWINKLE_NOISE: Noise = Noise::new(13);
+const TWINKLE_FRACTAL: Fractal = Fractal {
+    basis: Basis::Perlin,
+    mode: Mode::Ridged,
+    octaves: 2,
+    lacunarity: 2.0,
+    gain: 0.5,
+};
+const TWINKLE_ROW: f32 = 13.7;
+
+impl Sequencer<'_> {
+    // Play the first pattern from its start.
+    pub fn start(&mut self) {
+        self.sequence.pattern = 0;
+        self.sequence.time.reset();
+    }
+
+    // Play the next pattern, false if the last one was playing.
+    pub fn advance(&mut self) -> bool {
+        let count = self
+            .patterns
+            .get(&self.assets.patterns)
+            .map_or(0, |patterns| patterns.0.len());
+        if self.sequence.pattern + 1 >= count {
+            return false;
+        }
+
+        self.sequence.pattern += 1;
+        self.sequence.time.reset();
+        true
+    }
+}
+
+// Add the sequencer systems.
+pub fn add_systems(app: &mut App) {
+    data::register::<Patterns>(app, &["patterns.ron"]);
+
+    app.init_resource::<Sequence>()
+        .add_systems(Startup, init)
+        .add_systems(Update, handle_sequence.after(LightFlickerSet));
+}
+
+// Brightness of a light in the range [0, 1] for the pattern.
+fn brightness(pattern: &Pattern, light: &SequencedLight, groups: usize, morse: &[bool], time: f32) -> f32 {
+    let index = light.index as f32;
+    let lit = |on: bool| if on { 1.0 } else { 0.0 };
+
+    match pattern {
+        Pattern::Steady => 1.0,
+        Pattern::Chase { length, gap, speed } => lit(time.mul_add(-speed, index).rem_euclid(length + gap) < *length),
+        Pattern::Twinkle { speed, sharpness } => {
+            let noise = TWINKLE_NOISE.fractal_2d(&TWINKLE_FRACTAL, time * speed, index * TWINKLE_ROW);
+            noise.powf(*sharpness)
+        }
+        Pattern::Alternate { period } => lit((time / period) as usize % groups == light.group),
+        Pattern::Wave { wavelength, period } => ((index / wavelength - time / period) * TAU).sin().mul_add(0.5, 0.5),
+        Pattern::Morse { unit, .. } => {
+            let step = (time / unit) as usize;
+            lit(!morse.is_empty() && morse[step % morse.len()])
+        }
+    }
+}
+
+// Dim the lit lights along the string for the playing pattern, slowed down with reduced motion.
+fn handle_sequence(
+    time: Res<Time>,
+    accessibility: Res<AccessibilitySettings>,
+    assets: Res<SequencerAssets>,
+    patterns: Res<Assets<Patterns>>,
+    mut sequence: ResMut<Sequence>,
+    mut lights: Query<(&mut PointLight2d, &SequencedLight), With<FlickeringLight>>,
+) {
+    let speed = if accessibility.reduced_motion {
+        REDUCED_FLICKER_SPEED
+    } else {
+        1.0
+    };
+    sequence.time.tick(time.delta().mul_f32(speed));
+
+    let Some(pattern) = patterns
+        .get(&assets.patterns)
+        .and_then(|patterns| patterns.0.get(sequence.pattern))
+    else {
+        return;
+    };
+
+    let time = sequence.time.elapsed_secs();
+    let groups = lights.iter().map(|(_, light)| light.group + 1).max().unwrap_or(1);
+
+    // The message is the same for the whole string, so it's only encoded once.
+    let morse = match pattern {
+        Pattern::Morse { message, .. } => morse(message),
+        _ => Vec::new(),
+    };
+
+    for (mut light, sequenced) in &mut lights {
+        light.intensity *= brightness(pattern, sequenced, groups, &morse, time);
+    }
+}
+
+// On and off units of a message in morse code, ending with a word gap before it repeats.
+// NOTE: a dot is one unit on and a dash three, with one unit off between them, three between letters and seven between
+// words.
+fn morse(message: &str) -> Vec<bool> {
+    let mut units = Vec::new();
+
+    for word in message.split_whitespace() {
+        for code in word.chars().filter_map(morse_code) {
+            for symbol in code.chars() {
+                let length = if symbol == '-' { 3 } else { 1 };
+                units.extend(std::iter::repeat_n(true, length));
+                units.push(false);
+            }
+            units.extend([false; 2]);
+        }
+        units.extend([false; 4]);
+    }
+
+    units
+}
+
+// Morse code for a letter or digit.
+fn morse_code(letter: char) -> Option<&'static str> {
+    let code = match letter.to_ascii_uppercase() {
+        'A' => ".-",
+        'B' => "-...",
+        'C' => "-.-.",
+        'D' => "-..",
+        'E' => ".",
+        'F' => "..-.",
+        'G' => "--.",
+        'H' => "....",
+        'I' => "..",
+        'J' => ".---",
+        'K' => "-.-",
+        'L' => ".-..",
+        'M' => "--",
+        'N' => "-.",
+        'O' => "---",
+        'P' => ".--.",
+        'Q' => "--.-",
+        'R' => ".-.",
+        'S' => "...",
+        'T' => "-",
+        'U' => "..-",
+        'V' => "...-",
+        'W' => ".--",
+        'X' => "-..-",
+        'Y' => "-.--",
+        'Z' => "--..",
+        '0' => "-----",
+        '1' => ".----",
+        '2' => "..---",
+        '3' => "...--",
+        '4' => "....-",
+        '5' => ".....",
+        '6' => "-....",
+        '7' => "--...",
+        '8' => "---..",
+        '9' => "----.",
+        _ => return None,
+    };
+
+    Some(code)
+}
+
+// Load the patterns.
+fn init(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(SequencerAssets {
+        patterns: asset_server.load("lights/card.patterns.ron"),
+    });
+}
@@ -0,0 +1,77 @@
+use bevy::{audio::Volume, prelude::*};
+
+use crate::{camera::Framing, localization::Language};
+
+// Keys used to control the man.
+#[derive(Clone, Copy, Debug)]
+pub struct KeyBindings {
+    pub left: KeyCode,
+    pub right: KeyCode,
+    pub interact: KeyCode,
+}
+
+// User configurable settings.
+#[derive(Clone, Debug, Resource)]
+pub struct Settings {
+    pub volume: f32,
+    pub bindings: KeyBindings,
+    pub language: Language,
+    pub framing: Framing,
+}
+
+const VOLUME_STEP: f32 = 0.1;
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            left: KeyCode::ArrowLeft,
+            right: KeyCode::ArrowRight,
+            interact: KeyCode::ArrowUp,
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            bindings: KeyBindings::default(),
+            language: Language::default(),
+            framing: Framing::default(),
+        }
+    }
+}
+
+impl Settings {
+    // Raise or lower the volume by a number of steps.
+    pub fn step_volume(&mut self, step: i32) {
+        self.volume = (step as f32).mul_add(VOLUME_STEP, self.volume).clamp(0.0, 1.0);
+    }
+}
+
+// Add the settings systems.
+pub fn add_systems(app: &mut App) {
+    app.init_resource::<Settings>().add_systems(Update, handle_volume);
+}
+
+// Apply the volume setting to new and already playing sounds.
+// NOTE: sink volume starts as the playback volume multiplied by the global volume.
+fn handle_volume(
+    settings: Res<Settings>,
+    mut global_volume: ResMut<GlobalVolume>,
+    mut sinks: Query<(&mut AudioSink, &PlaybackSettings)>,
+    mut spatial_sinks: Query<(&mut SpatialAudioSink, &PlaybackSettings)>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    global_volume.volume = Volume::Linear(settings.volume);
+
+    for (mut sink, playback) in &mut sinks {
+        sink.set_volume(playback.volume * global_volume.volume);
+    }
+    for (mut sink, playback) in &mut spatial_sinks {
+        sink.set_volume(playback.volume * global_volume.volume);
+    }
+}
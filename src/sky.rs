@@ -0,0 +1,258 @@
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    time::Stopwatch,
+};
+use bevy_light_2d::prelude::*;
+use std::f32::consts::TAU;
+
+use crate::{
+    app::CardState,
+    background::Moonlight,
+    camera::WORLD_HEIGHT,
+    noise::{Basis, Fractal, Mode, Noise},
+};
+
+// Moon showing its phase on the day the card is opened.
+#[derive(Component)]
+struct Moon {
+    phase: f32,
+}
+
+// Clouds drifting across the sky, redrawn from noise as they move.
+#[derive(Component)]
+struct Clouds {
+    image: Handle<Image>,
+    timer: Timer,
+    // Time the clouds have been drifting for.
+    age: Stopwatch,
+}
+
+// Size of the cloud layer in pixels, each pixel covers CLOUD_PIXEL world units.
+const CLOUD_WIDTH: u32 = 150;
+const CLOUD_HEIGHT: u32 = 40;
+const CLOUD_PIXEL: f32 = 2.0;
+
+// Noise frequency per pixel, how far the clouds drift and change shape each second in pixels.
+const CLOUD_FREQUENCY: f32 = 1.0 / 24.0;
+const CLOUD_DRIFT: f32 = 1.5;
+const CLOUD_CHANGE: f32 = 0.2;
+
+// Clouds are billowed by the layered noise over the sky changing through time.
+static CLOUD_NOISE: Noise = Noise::new(17);
+const CLOUD_FRACTAL: Fractal = Fractal {
+    basis: Basis::Simplex,
+    mode: Mode::Fbm,
+    octaves: 3,
+    lacunarity: 2.0,
+    gain: 0.5,
+};
+
+// Noise below the threshold is clear sky, the rest fades in to the full opacity.
+const CLOUD_THRESHOLD: f32 = 0.05;
+const CLOUD_SOFTNESS: f32 = 0.3;
+const CLOUD_OPACITY: f32 = 0.85;
+const CLOUD_COLOR: [u8; 3] = [150, 160, 190];
+
+// Clouds are redrawn a few times a second, they move less than a pixel in between.
+const CLOUD_UPDATE_SECONDS: f32 = 0.1;
+
+// Moon in the upper left of the sky.
+const MOON_POSITION: Vec2 = Vec2::new(-128.0, 56.0);
+const MOON_SIZE: u32 = 11;
+const MOON_LIT: [u8; 4] = [250, 245, 220, 255];
+const MOON_DARK: [u8; 4] = [60, 70, 100, 255];
+
+// Average days from one new moon to the next and the unix time of a known new moon, 2000-01-06 18:14 UTC.
+const SYNODIC_MONTH: f64 = 29.530_588_853;
+const NEW_MOON_EPOCH: f64 = 947_182_440.0 / 86_400.0;
+
+// How much of the moonlight makes it through thick clouds and a new moon.
+const CLOUD_DIMMING: f32 = 0.7;
+const NEW_MOON_BRIGHTNESS: f32 = 0.4;
+
+// Add the sky systems.
+pub fn add_systems(app: &mut App) {
+    app.add_systems(OnEnter(CardState::Running), init)
+        .add_systems(Update, (handle_clouds, handle_moonlight).chain());
+}
+
+// Fraction of the moon that is lit, 0 at new moon and 1 at full moon.
+fn illumination(phase: f32) -> f32 {
+    (1.0 - (phase * TAU).cos()) / 2.0
+}
+
+// Cloud cover in the range [0, 1] at a cloud layer pixel.
+fn cloud_density(x: f32, y: f32, elapsed: f32) -> f32 {
+    let x = elapsed.mul_add(CLOUD_DRIFT, x) * CLOUD_FREQUENCY;
+    let noise = CLOUD_NOISE.fractal_3d(
+        &CLOUD_FRACTAL,
+        x,
+        y * CLOUD_FREQUENCY,
+        elapsed * CLOUD_CHANGE * CLOUD_FREQUENCY,
+    );
+
+    ((noise - CLOUD_THRESHOLD) / CLOUD_SOFTNESS).clamp(0.0, 1.0)
+}
+
+// Cloud layer pixel covering a world position.
+fn cloud_pixel(position: Vec2) -> Vec2 {
+    let top_left = Vec2::new(-(CLOUD_WIDTH as f32), CLOUD_HEIGHT as f32) * CLOUD_PIXEL / 2.0 + cloud_center();
+    Vec2::new(position.x - top_left.x, top_left.y - position.y) / CLOUD_PIXEL
+}
+
+// The cloud layer hangs from the top of the world.
+fn cloud_center() -> Vec2 {
+    Vec2::new(0.0, (WORLD_HEIGHT - CLOUD_HEIGHT as f32 * CLOUD_PIXEL) / 2.0)
+}
+
+// Draw the cloud layer pixels for the elapsed time.
+fn draw_clouds(data: &mut [u8], elapsed: f32) {
+    for (i, pixel) in data.chunks_exact_mut(4).enumerate() {
+        let x = (i as u32 % CLOUD_WIDTH) as f32;
+        let y = (i as u32 / CLOUD_WIDTH) as f32;
+
+        // Thin the clouds out toward the bottom of the layer so there's no hard edge.
+        let edge = (1.0 - y / CLOUD_HEIGHT as f32).min(0.5) * 2.0;
+        let alpha = cloud_density(x, y, elapsed) * edge * CLOUD_OPACITY;
+
+        pixel[..3].copy_from_slice(&CLOUD_COLOR);
+        pixel[3] = (alpha * 255.0) as u8;
+    }
+}
+
+// Redraw the clouds where they have drifted to.
+fn handle_clouds(time: Res<Time>, mut images: ResMut<Assets<Image>>, mut clouds: Query<&mut Clouds>) {
+    for mut clouds in &mut clouds {
+        clouds.age.tick(time.delta());
+        clouds.timer.tick(time.delta());
+        if !clouds.timer.just_finished() {
+            continue;
+        }
+
+        let Some(image) = images.get_mut(&clouds.image) else {
+            continue;
+        };
+        let Some(data) = &mut image.data else {
+            continue;
+        };
+
+        draw_clouds(data, clouds.age.elapsed_secs());
+    }
+}
+
+// Dim the moonlight for the moon phase and while clouds pass over the moon.
+fn handle_moonlight(moon: Single<&Moon>, clouds: Single<&Clouds>, mut lights: Query<(&mut SpotLight2d, &Moonlight)>) {
+    let pixel = cloud_pixel(MOON_POSITION);
+    let cover = cloud_density(pixel.x, pixel.y, clouds.age.elapsed_secs());
+    let phase = illumination(moon.phase).mul_add(1.0 - NEW_MOON_BRIGHTNESS, NEW_MOON_BRIGHTNESS);
+
+    for (mut light, moonlight) in &mut lights {
+        light.intensity = moonlight.intensity * phase * cover.mul_add(-CLOUD_DIMMING, 1.0);
+    }
+}
+
+// Draw the moon with the lit part for the phase, waxing from the right and waning to the left.
+fn moon_image(phase: f32) -> Image {
+    let radius = MOON_SIZE as f32 / 2.0;
+    let terminator = (phase * TAU).cos();
+    let mut data = Vec::with_capacity((MOON_SIZE * MOON_SIZE * 4) as usize);
+
+    for row in 0..MOON_SIZE {
+        for column in 0..MOON_SIZE {
+            let x = (column as f32 + 0.5 - radius) / radius;
+            let y = (row as f32 + 0.5 - radius) / radius;
+            if x * x + y * y > 1.0 {
+                data.extend_from_slice(&[0; 4]);
+                continue;
+            }
+
+            // Position across the row's width of the disk, the terminator is an ellipse across it.
+            let across = x / (1.0 - y * y).sqrt();
+            let lit = if phase < 0.5 {
+                across > terminator
+            } else {
+                across < -terminator
+            };
+            data.extend_from_slice(if lit { &MOON_LIT } else { &MOON_DARK });
+        }
+    }
+
+    Image::new(
+        Extent3d {
+            width: MOON_SIZE,
+            height: MOON_SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    )
+}
+
+// Where the moon is in its cycle for today, 0 at new moon and 0.5 at full moon.
+fn moon_phase() -> f32 {
+    ((unix_days() - NEW_MOON_EPOCH) / SYNODIC_MONTH).rem_euclid(1.0) as f32
+}
+
+// Days since the unix epoch from the browser's clock.
+#[cfg(target_arch = "wasm32")]
+fn unix_days() -> f64 {
+    web_sys::js_sys::Date::now() / 86_400_000.0
+}
+
+// Days since the unix epoch from the system clock.
+#[cfg(not(target_arch = "wasm32"))]
+fn unix_days() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0.0, |duration| duration.as_secs_f64() / 86_400.0)
+}
+
+// Sky initialization.
+// NOTE: the moon and clouds sit just in front of the background so everything else, including the falling snow, is in
+// front of them.
+fn init(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    // Moon for today's phase.
+    let phase = moon_phase();
+    commands.spawn((
+        Sprite {
+            image: images.add(moon_image(phase)),
+            ..default()
+        },
+        Transform::from_translation(MOON_POSITION.extend(0.1)),
+        DespawnOnExit(CardState::Running),
+        Moon { phase },
+    ));
+
+    // Clouds where they start out, redrawn as they drift.
+    let mut data = vec![0; (CLOUD_WIDTH * CLOUD_HEIGHT * 4) as usize];
+    draw_clouds(&mut data, 0.0);
+    let image = images.add(Image::new(
+        Extent3d {
+            width: CLOUD_WIDTH,
+            height: CLOUD_HEIGHT,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    ));
+    commands.spawn((
+        Sprite {
+            image: image.clone(),
+            custom_size: Some(Vec2::new(CLOUD_WIDTH as f32, CLOUD_HEIGHT as f32) * CLOUD_PIXEL),
+            ..default()
+        },
+        Transform::from_translation(cloud_center().extend(0.2)),
+        DespawnOnExit(CardState::Running),
+        Clouds {
+            image,
+            timer: Timer::from_seconds(CLOUD_UPDATE_SECONDS, TimerMode::Repeating),
+            age: Stopwatch::new(),
+        },
+    ));
+}
@@ -1,107 +1,144 @@
 use bevy::prelude::*;
-use rand::Rng;
 
-#[derive(Component)]
-struct SnowParticle {
-    fall_speed: f32,
-    drift_speed: f32,
-    drift_phase: f32,
-}
+use crate::{
+    app::CardState,
+    noise::{Basis, Fractal, Mode, Noise},
+    particles::{Emitter, Shape},
+    rooms::Room,
+    theman::{Sitting, TheMan},
+};
 
+// How heavily it's snowing, from 0 to 1, drifting slowly over time.
+#[derive(Resource, Default)]
+pub struct Snowfall(pub f32);
+
+// Snow falling over the whole card.
 #[derive(Component)]
 struct Snow;
 
+// Snow settled on the man's head, building up while he's outside and melting when he comes in.
 #[derive(Component)]
-struct Respawn;
-
-const PARTICLE_COUNT: usize = 700;
+struct SnowCover(f32);
 
+// Flakes a second in the heaviest snowfall, they fall from above the card until they're below it.
+// NOTE: snow also falls behind the house so the wind has snow to blow out across the sky.
+const SNOW_RATE: f32 = 100.0;
 const SPAWN_Y: f32 = 100.0;
 const DESPAWN_Y: f32 = -80.0;
-const SPAWN_X_MIN: f32 = -150.0;
-const SPAWN_X_MAX: f32 = 75.0;
+const SPAWN_WIDTH: f32 = 300.0;
 
 const FALL_SPEED_MIN: f32 = 10.0;
 const FALL_SPEED_MAX: f32 = 30.0;
+const DRIFT_SPEED: f32 = 7.0;
 
-const DRIFT_SPEED_MIN: f32 = -7.0;
-const DRIFT_SPEED_MAX: f32 = 7.0;
+// Flakes are more or less see through.
+const OPACITIES: [f32; 4] = [0.4, 0.6, 0.8, 1.0];
 
-const OPACITY_MIN: f32 = 0.4;
-const OPACITY_MAX: f32 = 1.0;
+// Lightest snowfall and how quickly it changes, a full cycle takes a few minutes.
+const SNOWFALL_MIN: f32 = 0.3;
+const SNOWFALL_FREQUENCY: f32 = 1.0 / 90.0;
+
+// Snow comes in flurries, easing off to the lightest snowfall between them.
+static SNOWFALL_NOISE: Noise = Noise::new(42);
+const SNOWFALL_FRACTAL: Fractal = Fractal {
+    basis: Basis::Perlin,
+    mode: Mode::Turbulence,
+    octaves: 2,
+    lacunarity: 2.0,
+    gain: 0.5,
+};
+
+// Seconds outside until the man is fully covered and seconds inside for it all to melt.
+const COVER_SECONDS: f32 = 30.0;
+const MELT_SECONDS: f32 = 10.0;
 
 // Add the snow systems.
 pub fn add_systems(app: &mut App) {
-    app.add_systems(Startup, init)
-        .add_systems(Update, (handle_snow, handle_snow_respawn));
+    app.init_resource::<Snowfall>()
+        .add_systems(OnEnter(CardState::Running), init)
+        .add_systems(Update, (handle_snowfall, handle_cover_spawn, handle_cover));
 }
 
-// Handle snow particle movement with vertical falling and horizontal wind drift and mark particles that are too low.
-fn handle_snow(
-    mut commands: Commands,
-    time: Res<Time>,
-    mut query: Query<(Entity, &mut Transform, &SnowParticle), (With<Snow>, Without<Respawn>)>,
-) {
-    for (entity, mut transform, particle) in &mut query {
-        let delta = time.delta_secs();
-
-        // Vertical fall with a constant speed per particle.
-        transform.translation.y -= particle.fall_speed * delta;
-
-        // Horizontal drift with a sine wave for motion.
-        let drift_offset = (time.elapsed_secs() + particle.drift_phase).sin();
-        transform.translation.x += particle.drift_speed * drift_offset * delta;
-
-        if transform.translation.y < DESPAWN_Y {
-            commands.entity(entity).insert(Respawn);
-        }
-    }
+// How heavily it's snowing after the given seconds.
+fn snowfall_at(elapsed: f32) -> f32 {
+    let noise = SNOWFALL_NOISE.fractal_1d(&SNOWFALL_FRACTAL, elapsed * SNOWFALL_FREQUENCY);
+    let heaviness = (noise * 2.0).min(1.0);
+    heaviness.mul_add(1.0 - SNOWFALL_MIN, SNOWFALL_MIN)
 }
 
-// Respawn snow particles that have been marked as below the screen.
-fn handle_snow_respawn(
-    mut commands: Commands,
-    mut query: Query<(Entity, &mut Transform, &mut Sprite, &mut SnowParticle), (With<Snow>, With<Respawn>)>,
-) {
-    let mut rng = rand::rng();
-
-    for (entity, mut transform, mut sprite, mut particle) in &mut query {
-        transform.translation.x = rng.random_range(SPAWN_X_MIN..=SPAWN_X_MAX);
-        transform.translation.y = SPAWN_Y;
-
-        particle.fall_speed = rng.random_range(FALL_SPEED_MIN..=FALL_SPEED_MAX);
-        particle.drift_speed = rng.random_range(DRIFT_SPEED_MIN..=DRIFT_SPEED_MAX);
-        particle.drift_phase = rng.random_range(0.0..=std::f32::consts::TAU);
+// Vary how heavily it's snowing with slow noise.
+fn handle_snowfall(time: Res<Time>, mut snowfall: ResMut<Snowfall>, mut snow: Query<&mut Emitter, With<Snow>>) {
+    snowfall.0 = snowfall_at(time.elapsed_secs());
 
-        let opacity = rng.random_range(OPACITY_MIN..=OPACITY_MAX);
-        sprite.color = Color::srgba(1.0, 1.0, 1.0, opacity);
-
-        commands.entity(entity).remove::<Respawn>();
+    for mut emitter in &mut snow {
+        emitter.strength = snowfall.0;
     }
 }
 
-// Initialize snow particles distributed across the screen.
-fn init(mut commands: Commands) {
-    let mut rng = rand::rng();
-
-    for _ in 0..PARTICLE_COUNT {
-        let x = rng.random_range(SPAWN_X_MIN..=SPAWN_X_MAX);
-        let y = rng.random_range(DESPAWN_Y..=SPAWN_Y);
-        let opacity = rng.random_range(OPACITY_MIN..=OPACITY_MAX);
-
-        commands.spawn((
+// Give the man somewhere for snow to settle.
+fn handle_cover_spawn(mut commands: Commands, asset_server: Res<AssetServer>, query: Query<Entity, Added<TheMan>>) {
+    for entity in &query {
+        commands.entity(entity).with_child((
             Sprite {
-                color: Color::srgba(1.0, 1.0, 1.0, opacity),
-                custom_size: Some(Vec2::splat(1.0)),
+                image: asset_server.load("theman/snow.png"),
+                color: Color::NONE,
                 ..default()
             },
-            Transform::from_translation(Vec3::new(x, y, 1.0)),
-            SnowParticle {
-                fall_speed: rng.random_range(FALL_SPEED_MIN..=FALL_SPEED_MAX),
-                drift_speed: rng.random_range(DRIFT_SPEED_MIN..=DRIFT_SPEED_MAX),
-                drift_phase: rng.random_range(0.0..=std::f32::consts::TAU),
-            },
-            Snow,
+            Transform::from_xyz(0.0, 0.0, 0.1),
+            SnowCover(0.0),
         ));
     }
 }
+
+// Build up snow on the man outside and melt it indoors, hidden while he's sitting since his head moves.
+fn handle_cover(
+    time: Res<Time>,
+    room: Res<State<Room>>,
+    snowfall: Res<Snowfall>,
+    man: Single<(&Sprite, Has<Sitting>), (With<TheMan>, Without<SnowCover>)>,
+    mut query: Query<(&mut SnowCover, &mut Sprite, &mut Visibility)>,
+) {
+    let (man_sprite, sitting) = *man;
+
+    for (mut cover, mut sprite, mut visibility) in &mut query {
+        cover.0 = if *room.get() == Room::Porch {
+            (cover.0 + snowfall.0 * time.delta_secs() / COVER_SECONDS).min(1.0)
+        } else {
+            (cover.0 - time.delta_secs() / MELT_SECONDS).max(0.0)
+        };
+
+        sprite.color = Color::WHITE.with_alpha(cover.0);
+        sprite.flip_x = man_sprite.flip_x;
+        visibility.set_if_neq(if sitting {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        });
+    }
+}
+
+// Start the snow with flakes already falling all the way down the card.
+fn init(mut commands: Commands, time: Res<Time>, mut snowfall: ResMut<Snowfall>) {
+    // Work out the snowfall now so the prewarmed snow is as heavy as it's about to be.
+    snowfall.0 = snowfall_at(time.elapsed_secs());
+    let fall = (SPAWN_Y - DESPAWN_Y) / FALL_SPEED_MIN;
+    let tints = OPACITIES.map(|opacity| Color::WHITE.with_alpha(opacity));
+
+    commands.spawn((
+        Snow,
+        Transform::from_xyz(0.0, SPAWN_Y, 1.0),
+        DespawnOnExit(CardState::Running),
+        Emitter::new(
+            SNOW_RATE,
+            Shape::Box(Vec2::new(SPAWN_WIDTH / 2.0, 0.0)),
+            (fall, fall),
+            (Vec2::new(0.0, -FALL_SPEED_MAX), Vec2::new(0.0, -FALL_SPEED_MIN)),
+        )
+        .with_strength(snowfall.0)
+        .with_wind(1.0)
+        .with_wobble(DRIFT_SPEED)
+        .with_tints(&tints)
+        .with_bounds(Rect::new(-SPAWN_WIDTH, DESPAWN_Y, SPAWN_WIDTH, SPAWN_Y))
+        .with_prewarm(fall),
+    ));
+}
@@ -1,22 +1,112 @@
 use bevy::prelude::*;
 
+use crate::{
+    app::CardState,
+    interaction::{Interactable, InteractionEvent, State, StateSet},
+    rooms::{InRoom, Room},
+};
+
+// Snowman out on the porch, built up a stage at a time.
 #[derive(Component)]
-struct Snowman;
+struct Snowman {
+    stage: usize,
+}
+
+const INTERACTABLE_ID: &str = "snowman";
+
+// Stages are the bottom ball, middle ball, head, hat, scarf and carrot, one sprite sheet frame each.
+const STAGES: usize = 6;
 
 // Add the animation systems.
 pub fn add_systems(app: &mut App) {
-    app.add_systems(Startup, init);
+    app.add_systems(OnEnter(CardState::Running), init).add_systems(
+        Update,
+        (
+            handle_interaction.in_set(StateSet::Change),
+            handle_state.in_set(StateSet::Effects),
+        ),
+    );
+}
+
+// Add the next piece on each interaction, a finished snowman is knocked down to start over.
+fn handle_interaction(
+    mut events: MessageReader<InteractionEvent>,
+    mut query: Query<(&mut Snowman, &mut State, &mut Sprite)>,
+) {
+    for event in events.read() {
+        if event.id != INTERACTABLE_ID {
+            continue;
+        }
+
+        for (mut snowman, mut state, mut sprite) in &mut query {
+            snowman.stage = (snowman.stage + 1) % STAGES;
+
+            if let Some(atlas) = &mut sprite.texture_atlas {
+                atlas.index = snowman.stage;
+            }
+
+            let finished = if snowman.stage == STAGES - 1 {
+                State::On
+            } else {
+                State::Off
+            };
+            state.set_if_neq(finished);
+        }
+    }
+}
+
+// Finish or knock down the snowman when it's turned on or off without being built, e.g. by a timeline.
+fn handle_state(mut query: Query<(&mut Snowman, &State, &mut Sprite), Changed<State>>) {
+    for (mut snowman, state, mut sprite) in &mut query {
+        let stage = match *state {
+            State::On => STAGES - 1,
+            State::Off if snowman.stage == STAGES - 1 => 0,
+            State::Off => continue,
+        };
+
+        if snowman.stage != stage {
+            snowman.stage = stage;
+            if let Some(atlas) = &mut sprite.texture_atlas {
+                atlas.index = stage;
+            }
+        }
+    }
 }
 
 // Snowman initialization.
-fn init(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let background = asset_server.load("snowman/snowman.png");
+fn init(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut texture_layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    // The snowman starts as a single rolled up ball of snow.
     commands.spawn((
         Sprite {
-            image: background,
+            image: asset_server.load("snowman/snowman_stages.png"),
+            texture_atlas: Some(TextureAtlas {
+                layout: texture_layouts.add(TextureAtlasLayout::from_grid(
+                    UVec2::splat(32),
+                    STAGES as u32,
+                    1,
+                    None,
+                    None,
+                )),
+                index: 0,
+            }),
             ..default()
         },
         Transform::from_xyz(-124.0, -53.0, 1.0),
-        Snowman,
+        DespawnOnExit(CardState::Running),
+        InRoom(Room::Porch),
+        Snowman { stage: 0 },
+        State::Off,
+        Interactable {
+            id: INTERACTABLE_ID.to_string(),
+            height: 32.0,
+            width: 20.0,
+            sprite_height: 32.0,
+            sprite_width: 32.0,
+            ..default()
+        },
     ));
 }
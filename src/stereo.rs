@@ -2,7 +2,9 @@ use bevy::{audio::Volume, prelude::*};
 
 use crate::{
     animation::AnimationConfig,
-    interaction::{Interactable, InteractionEvent, State},
+    app::CardState,
+    interaction::{Interactable, InteractionEvent, State, StateSet},
+    rooms::{InRoom, Room},
 };
 
 #[derive(Clone, Resource)]
@@ -15,12 +17,21 @@ struct SpriteAssets {
 #[derive(Component)]
 struct Stereo;
 
+// Filter for the stereo when its state changes.
+type ChangedStereo = (With<Stereo>, Changed<State>);
+
 const INTERACTABLE_ID: &str = "stereo";
 
 // Add the animation systems.
 pub fn add_systems(app: &mut App) {
-    app.add_systems(Startup, init)
-        .add_systems(Update, (handle_animations, handle_interaction, handle_sound));
+    app.add_systems(OnEnter(CardState::Running), init).add_systems(
+        Update,
+        (
+            handle_animations,
+            handle_interaction.in_set(StateSet::Change),
+            (handle_sprite, handle_sound).in_set(StateSet::Effects),
+        ),
+    );
 }
 
 // Manage the animation frame timing.
@@ -49,37 +60,41 @@ fn handle_animations(time: Res<Time>, mut query: Query<(&mut AnimationConfig, &m
 }
 
 // Listen for interaction events and update the state.
-fn handle_interaction(
-    sprite_assets: Res<SpriteAssets>,
-    mut events: MessageReader<InteractionEvent>,
-    mut query: Query<(&mut State, &mut Sprite), With<Stereo>>,
-) {
+fn handle_interaction(mut events: MessageReader<InteractionEvent>, mut query: Query<&mut State, With<Stereo>>) {
     for event in events.read() {
         if event.id == INTERACTABLE_ID
-            && let Ok((mut state, mut sprite)) = query.single_mut()
+            && let Ok(mut state) = query.single_mut()
         {
-            match *state {
-                State::Off => {
-                    *state = State::On;
-                    sprite.image = sprite_assets.running_sprite.clone();
-                    sprite.texture_atlas = Some(TextureAtlas {
-                        layout: sprite_assets.running_layout.clone(),
-                        index: 0,
-                    });
-                }
-
-                State::On => {
-                    *state = State::Off;
-                    sprite.image = sprite_assets.off_sprite.clone();
-                    sprite.texture_atlas = None;
-                }
+            *state = match *state {
+                State::Off => State::On,
+                State::On => State::Off,
+            };
+        }
+    }
+}
+
+// Swap between the running and off sprites when the state changes.
+fn handle_sprite(sprite_assets: Res<SpriteAssets>, mut query: Query<(&State, &mut Sprite), ChangedStereo>) {
+    for (state, mut sprite) in &mut query {
+        match *state {
+            State::On => {
+                sprite.image = sprite_assets.running_sprite.clone();
+                sprite.texture_atlas = Some(TextureAtlas {
+                    layout: sprite_assets.running_layout.clone(),
+                    index: 0,
+                });
+            }
+
+            State::Off => {
+                sprite.image = sprite_assets.off_sprite.clone();
+                sprite.texture_atlas = None;
             }
         }
     }
 }
 
 // Control audio playback based on stereo state
-fn handle_sound(query: Query<(&State, &mut SpatialAudioSink), (With<Stereo>, Changed<State>)>) {
+fn handle_sound(query: Query<(&State, &mut SpatialAudioSink), ChangedStereo>) {
     for (state, audio_sink) in &query {
         match *state {
             // Start the stereo sound effect if it isn't already running.
@@ -117,6 +132,8 @@ fn init(
             ..default()
         },
         Transform::from_translation(Vec3::new(18.0, -46.0, 5.0)),
+        DespawnOnExit(CardState::Running),
+        InRoom(Room::Living),
         Stereo,
         AnimationConfig::new(0, 4, 4),
         State::Off,
@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+
+use crate::app::CardState;
+
+// Floor materials that change how footsteps sound.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Surface {
+    #[default]
+    Wood,
+    Rug,
+    Snow,
+    Stone,
+}
+
+// Tags an area of the floor with a surface.
+#[derive(Component)]
+pub struct SurfaceRegion {
+    pub surface: Surface,
+    pub area: Rect,
+}
+
+// Add the surface systems.
+pub fn add_systems(app: &mut App) {
+    app.add_systems(OnEnter(CardState::Running), init);
+}
+
+// Find the surface under a position, the smallest containing region wins so rugs can lie on top of floors.
+pub fn surface_at(position: Vec2, regions: &Query<&SurfaceRegion>) -> Surface {
+    regions
+        .iter()
+        .filter(|region| region.area.contains(position))
+        .min_by(|a, b| a.area.size().x.total_cmp(&b.area.size().x))
+        .map_or_else(Surface::default, |region| region.surface)
+}
+
+// Surface initialization.
+fn init(mut commands: Commands, asset_server: Res<AssetServer>) {
+    // Living room floorboards.
+    commands.spawn((
+        SurfaceRegion {
+            surface: Surface::Wood,
+            area: Rect::new(-90.0, -80.0, 175.0, -60.0),
+        },
+        DespawnOnExit(CardState::Running),
+    ));
+
+    // Stone hearth in front of the fireplace.
+    commands.spawn((
+        SurfaceRegion {
+            surface: Surface::Stone,
+            area: Rect::new(96.0, -80.0, 136.0, -60.0),
+        },
+        DespawnOnExit(CardState::Running),
+    ));
+
+    // Rug under the chair.
+    commands.spawn((
+        Sprite {
+            image: asset_server.load("house/rug.png"),
+            ..default()
+        },
+        Transform::from_xyz(75.0, -69.0, 3.0),
+        DespawnOnExit(CardState::Running),
+        SurfaceRegion {
+            surface: Surface::Rug,
+            area: Rect::new(55.0, -80.0, 95.0, -60.0),
+        },
+    ));
+
+    // Snow outside the house.
+    commands.spawn((
+        SurfaceRegion {
+            surface: Surface::Snow,
+            area: Rect::new(-175.0, -80.0, -90.0, -60.0),
+        },
+        DespawnOnExit(CardState::Running),
+    ));
+}
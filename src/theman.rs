@@ -1,13 +1,24 @@
-use bevy::{audio::Volume, prelude::*};
+use bevy::{
+    audio::Volume,
+    platform::collections::HashMap,
+    prelude::{State as CurrentState, *},
+};
 use rand::{Rng, rng};
-use std::time::Duration;
 
 use crate::{
     animation::AnimationConfig,
+    app::CardState,
     chair,
+    dialogue::DialogueState,
     input::{Direction, InputEvent},
-    interaction::{InRange, InteractionEvent, Interactor},
+    interaction::{self, InRange, Interactable, InteractionEvent, Interactor},
+    menu::MenuState,
+    rooms::{Fade, Room},
     santa::SantasHereEvent,
+    snow::Snowfall,
+    surface::{self, Surface, SurfaceRegion},
+    timeline::{Actor, CutsceneState},
+    warmth::{COLD, Warmth},
 };
 
 #[derive(Component, Clone, Copy, Debug, PartialEq)]
@@ -18,17 +29,42 @@ enum State {
     Sitting,
 }
 
-#[derive(Component, Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq)]
 enum FootStep {
     Left,
     Right,
 }
 
-#[derive(Component)]
-struct IdleTimer(Timer);
+// Tracks the last walking frame checked for a foot contact.
+#[derive(Component, Default)]
+struct FootContact {
+    last_index: Option<usize>,
+}
+
+// Things the man does by himself while standing around.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum IdleAction {
+    LookAround,
+    Stretch,
+    WarmHands,
+    Dance,
+    WatchSnow,
+    Shiver,
+}
 
+// Counts down to the next idle action and plays the current one.
 #[derive(Component)]
-struct StepTimer(Timer);
+struct Idle {
+    timer: Timer,
+    playing: Option<IdlePlayback>,
+}
+
+// An idle action being played, with the man's position to return to when it's done.
+struct IdlePlayback {
+    action: IdleAction,
+    timer: Timer,
+    base_y: f32,
+}
 
 #[derive(Component)]
 struct Navigation {
@@ -36,10 +72,18 @@ struct Navigation {
     action: bool,
 }
 
+// Footstep sounds and playback settings for a single surface.
+#[derive(Clone)]
+struct FootstepSet {
+    left: Vec<Handle<AudioSource>>,
+    right: Vec<Handle<AudioSource>>,
+    volume: f32,
+    speed: f32,
+}
+
 #[derive(Clone, Resource)]
 struct AudioAssets {
-    left_steps: Vec<Handle<AudioSource>>,
-    right_steps: Vec<Handle<AudioSource>>,
+    footsteps: HashMap<Surface, FootstepSet>,
 }
 
 #[derive(Clone, Resource)]
@@ -50,30 +94,87 @@ struct SpriteAssets {
     sitting_layout: Handle<TextureAtlasLayout>,
     standing_sprite: Handle<Image>,
     standing_layout: Handle<TextureAtlasLayout>,
+    idle_sprite: Handle<Image>,
+    idle_layout: Handle<TextureAtlasLayout>,
 }
 
 #[derive(Component)]
 pub struct TheMan;
 
+// Marks the man while he's sitting in the chair.
+#[derive(Component)]
+pub struct Sitting;
+
 const WALKING_SPEED: f32 = 30.0;
-const WALKING_VOLUME: f32 = 0.85;
-const WALKING_TIMER: f32 = 0.45;
+
+// Distance from the sprite center down to the man's feet.
+const FEET_OFFSET: f32 = 16.0;
+
+// Walking animation frames where a foot touches the floor.
+const FOOT_CONTACT_FRAMES: [(usize, FootStep); 2] = [(3, FootStep::Left), (7, FootStep::Right)];
+
+// Footstep recording name, volume and playback speed for each surface.
+// NOTE: the indoor recordings are shared by the indoor surfaces with different playback settings.
+const FOOTSTEP_SETS: [(Surface, &str, f32, f32); 4] = [
+    (Surface::Wood, "indoor", 0.85, 1.0),
+    (Surface::Rug, "indoor", 0.35, 0.85),
+    (Surface::Stone, "indoor", 0.95, 1.2),
+    (Surface::Snow, "snow", 0.6, 1.0),
+];
+const FOOTSTEP_VARIATIONS: usize = 3;
+
+// Weight and length in seconds of each idle action, the scene decides which ones can be picked.
+const IDLE_ACTIONS: [(IdleAction, f32, f32); 6] = [
+    (IdleAction::LookAround, 3.0, 0.0),
+    (IdleAction::Stretch, 1.0, 2.0),
+    (IdleAction::WarmHands, 4.0, 5.0),
+    (IdleAction::Dance, 6.0, 6.0),
+    (IdleAction::WatchSnow, 4.0, 6.0),
+    (IdleAction::Shiver, 8.0, 3.0),
+];
+
+// Random wait in seconds between idle actions.
+const IDLE_WAIT: (f32, f32) = (3.0, 7.0);
+
+// How close something needs to be for the man to react to it.
+const NEARBY_DISTANCE: f32 = 50.0;
+
+// Snowfall heavy enough for the man to watch it.
+const HEAVY_SNOW: f32 = 0.7;
+
+// Living room windows as ranges along the back wall.
+const WINDOWS: [(f32, f32); 2] = [(-42.0, -2.0), (38.0, 78.0)];
+
+// Seconds per dance step, per shiver and per rub of the hands.
+const DANCE_BEAT: f32 = 0.4;
+const SHIVER_BEAT: f32 = 0.08;
+const RUB_BEAT: f32 = 0.25;
+
+// Idle sprite sheet frames, arms halfway and all the way up for stretching and hands held out rubbing together.
+const STRETCH_FRAMES: [usize; 2] = [0, 1];
+const WARM_HANDS_FRAMES: [usize; 2] = [2, 3];
 
 // Add the animation systems.
 pub fn add_systems(app: &mut App) {
-    app.add_message::<InputEvent>().add_systems(Startup, init).add_systems(
-        Update,
-        (
-            handle_audio,
-            handle_animations,
-            handle_animation_state_change.before(handle_animations),
-            handle_interactions,
-            handle_messages.before(handle_animation_state_change),
-            handle_movement,
-            handle_idle_action,
-            handle_chair_interaction,
-        ),
-    );
+    app.add_message::<InputEvent>()
+        .add_systems(OnEnter(CardState::Running), init)
+        .add_systems(OnEnter(DialogueState::Open), handle_input_blocked)
+        .add_systems(OnEnter(MenuState::Open), handle_input_blocked)
+        .add_systems(OnEnter(CutsceneState::Playing), handle_input_blocked)
+        .add_systems(
+            Update,
+            (
+                handle_audio.after(handle_animations),
+                handle_animations,
+                handle_animation_state_change.before(handle_animations),
+                handle_interactions,
+                handle_messages.before(handle_animation_state_change),
+                handle_movement,
+                handle_idle.after(handle_animation_state_change),
+                handle_chair_interaction,
+                handle_input_blocked.run_if(any_match_filter::<Added<Fade>>),
+            ),
+        );
 }
 
 // Advance animation frames and states.
@@ -164,65 +265,64 @@ fn handle_animation_state_change(
     }
 }
 
-// Runs every frame to tick footstep timer during Walking state.
+// Play a footstep for the current surface whenever the walking animation reaches a foot contact frame.
 fn handle_audio(
     mut commands: Commands,
-    time: Res<Time>,
     audio_assets: Res<AudioAssets>,
-    mut query: Query<(&State, &mut StepTimer, &mut FootStep), With<TheMan>>,
+    regions: Query<&SurfaceRegion>,
+    mut query: Query<(&State, &Sprite, &Transform, &mut FootContact), With<TheMan>>,
 ) {
-    for (state, mut timer, mut footstep) in &mut query {
-        match *state {
-            State::Walking => {
-                timer.0.tick(time.delta());
-                if timer.0.just_finished() {
-                    match *footstep {
-                        FootStep::Left => {
-                            commands.spawn((
-                                AudioPlayer::new(
-                                    audio_assets.left_steps[rng().random_range(0..audio_assets.left_steps.len())]
-                                        .clone(),
-                                ),
-                                PlaybackSettings::DESPAWN.with_volume(Volume::Linear(WALKING_VOLUME)),
-                            ));
-                            timer.0.set_duration(Duration::from_secs_f32(WALKING_TIMER));
-                            *footstep = FootStep::Right;
-                        }
-
-                        FootStep::Right => {
-                            commands.spawn((
-                                AudioPlayer::new(
-                                    audio_assets.right_steps[rng().random_range(0..audio_assets.right_steps.len())]
-                                        .clone(),
-                                ),
-                                PlaybackSettings::DESPAWN.with_volume(Volume::Linear(WALKING_VOLUME)),
-                            ));
-                            timer.0.set_duration(Duration::from_secs_f32(WALKING_TIMER));
-                            *footstep = FootStep::Left;
-                        }
-                    }
-                }
-            }
+    for (state, sprite, transform, mut contact) in &mut query {
+        let index = match (*state, &sprite.texture_atlas) {
+            (State::Walking, Some(atlas)) => atlas.index,
             _ => {
-                timer.0.set_duration(Duration::from_secs_f32(0.225));
+                contact.last_index = None;
+                continue;
             }
+        };
+
+        // Only check each frame once.
+        if contact.last_index == Some(index) {
+            continue;
         }
+        contact.last_index = Some(index);
+
+        let Some((_, foot)) = FOOT_CONTACT_FRAMES.iter().find(|(frame, _)| *frame == index) else {
+            continue;
+        };
+
+        let feet = transform.translation.truncate() - Vec2::new(0.0, FEET_OFFSET);
+        let Some(set) = audio_assets.footsteps.get(&surface::surface_at(feet, &regions)) else {
+            continue;
+        };
+
+        let steps = match foot {
+            FootStep::Left => &set.left,
+            FootStep::Right => &set.right,
+        };
+        commands.spawn((
+            AudioPlayer::new(steps[rng().random_range(0..steps.len())].clone()),
+            PlaybackSettings::DESPAWN
+                .with_volume(Volume::Linear(set.volume))
+                .with_speed(set.speed),
+        ));
     }
 }
 
 // Handle chair-specific interactions for sitting/standing.
 fn handle_chair_interaction(
+    mut commands: Commands,
     sprite_assets: Res<SpriteAssets>,
     mut events: MessageReader<InteractionEvent>,
     mut santa_events: MessageWriter<SantasHereEvent>,
-    mut man_query: Query<(&mut State, &mut Sprite, &mut Transform, &mut AnimationConfig), With<TheMan>>,
+    mut man_query: Query<(Entity, &mut State, &mut Sprite, &mut Transform, &mut AnimationConfig), With<TheMan>>,
 ) {
     for event in events.read() {
         if event.id != chair::INTERACTABLE_ID {
             continue;
         }
 
-        if let Ok((mut state, mut sprite, mut transform, mut config)) = man_query.single_mut() {
+        if let Ok((entity, mut state, mut sprite, mut transform, mut config)) = man_query.single_mut() {
             match *state {
                 State::Action => {
                     // Teleport to the chair sitting position.
@@ -244,6 +344,7 @@ fn handle_chair_interaction(
                     config.frame_timer = AnimationConfig::timer_from_fps(10);
 
                     *state = State::Sitting;
+                    commands.entity(entity).insert(Sitting);
                     santa_events.write(SantasHereEvent);
                 }
 
@@ -258,6 +359,7 @@ fn handle_chair_interaction(
                     });
 
                     *state = State::Idle;
+                    commands.entity(entity).remove::<Sitting>();
                 }
 
                 _ => {}
@@ -266,17 +368,153 @@ fn handle_chair_interaction(
     }
 }
 
-// Change the man's direction using the idle timer.
-fn handle_idle_action(time: Res<Time>, mut query: Query<(&mut IdleTimer, &mut Sprite, &State), With<TheMan>>) {
-    for (mut timer, mut sprite, state) in &mut query {
-        if *state == State::Idle {
-            timer.0.tick(time.delta());
-            if timer.0.just_finished() {
-                sprite.flip_x = !sprite.flip_x;
+// Pick idle actions from what's going on around the man and how warm he is and play them, stopping as soon as he does
+// anything else.
+// NOTE: a cold man shivers and warms his hands at the fire rather than stretching or dancing.
+fn handle_idle(
+    time: Res<Time>,
+    sprite_assets: Res<SpriteAssets>,
+    room: Res<CurrentState<Room>>,
+    snowfall: Res<Snowfall>,
+    warmth: Res<Warmth>,
+    interactables: Query<(&Interactable, &interaction::State, &GlobalTransform)>,
+    mut query: Query<(&State, &mut Idle, &mut Sprite, &mut Transform), With<TheMan>>,
+) {
+    for (state, mut idle, mut sprite, mut transform) in &mut query {
+        if *state != State::Idle {
+            if let Some(playing) = idle.playing.take() {
+                transform.translation.y = playing.base_y;
             }
-        } else {
-            timer.0.reset();
+            idle.timer.reset();
+            continue;
         }
+
+        // Play the current action until it's done.
+        if let Some(playing) = &mut idle.playing {
+            playing.timer.tick(time.delta());
+            let elapsed = playing.timer.elapsed_secs();
+
+            let (frame, hop) = match playing.action {
+                // Arms go up halfway, all the way for most of the stretch and halfway again on the way down.
+                IdleAction::Stretch => {
+                    let up = (0.25..0.75).contains(&playing.timer.fraction());
+                    (Some(STRETCH_FRAMES[usize::from(up)]), 0.0)
+                }
+                IdleAction::WarmHands => (Some(WARM_HANDS_FRAMES[(elapsed / RUB_BEAT) as usize % 2]), 0.0),
+                IdleAction::Dance => {
+                    let beat = (elapsed / DANCE_BEAT) as u32;
+                    sprite.flip_x = beat.is_multiple_of(2);
+                    (None, 2.0 * (std::f32::consts::PI * elapsed / DANCE_BEAT).sin().abs())
+                }
+                IdleAction::Shiver => (None, ((elapsed / SHIVER_BEAT) as u32 % 2) as f32),
+                IdleAction::LookAround | IdleAction::WatchSnow => (None, 0.0),
+            };
+
+            if let Some(frame) = frame
+                && let Some(atlas) = &mut sprite.texture_atlas
+            {
+                atlas.index = frame;
+            }
+            transform.translation.y = playing.base_y + hop.round();
+
+            // Stand normally again once it's done.
+            if playing.timer.is_finished() {
+                sprite.image = sprite_assets.standing_sprite.clone();
+                sprite.texture_atlas = Some(TextureAtlas {
+                    layout: sprite_assets.standing_layout.clone(),
+                    index: 0,
+                });
+                transform.translation.y = playing.base_y;
+                idle.playing = None;
+            }
+            continue;
+        }
+
+        idle.timer.tick(time.delta());
+        if !idle.timer.is_finished() {
+            continue;
+        }
+        let mut rng = rng();
+        idle.timer = Timer::from_seconds(rng.random_range(IDLE_WAIT.0..=IDLE_WAIT.1), TimerMode::Once);
+
+        // Find what's on and close enough to react to.
+        let x = transform.translation.x;
+        let nearby_on = |id: &str| {
+            interactables.iter().find_map(|(interactable, state, other)| {
+                let other_x = other.translation().x;
+                (interactable.id == id && *state == interaction::State::On && (other_x - x).abs() <= NEARBY_DISTANCE)
+                    .then_some(other_x)
+            })
+        };
+        let living_room = *room.get() == Room::Living;
+        let fire = nearby_on("fireplace").filter(|_| living_room);
+        let music = living_room
+            && interactables
+                .iter()
+                .any(|(interactable, state, _)| interactable.id == "stereo" && *state == interaction::State::On);
+        // He watches the snow through the window he's standing at,
+        // or watches it settle on the snowman out on the porch.
+        let window = WINDOWS
+            .iter()
+            .find(|(left, right)| living_room && (*left..=*right).contains(&x))
+            .map(|(left, right)| (left + right) / 2.0)
+            .or_else(|| {
+                interactables
+                    .iter()
+                    .find(|(interactable, ..)| *room.get() == Room::Porch && interactable.id == "snowman")
+                    .map(|(.., snowman)| snowman.translation().x)
+            })
+            .filter(|_| snowfall.0 >= HEAVY_SNOW);
+        let cold = warmth.0 < COLD;
+
+        let available: Vec<(IdleAction, f32, f32)> = IDLE_ACTIONS
+            .into_iter()
+            .filter(|(action, _, _)| match action {
+                IdleAction::LookAround => true,
+                IdleAction::Stretch => !cold,
+                IdleAction::WarmHands => fire.is_some(),
+                IdleAction::Dance => music && !cold,
+                IdleAction::WatchSnow => window.is_some(),
+                IdleAction::Shiver => cold,
+            })
+            .collect();
+        let total: f32 = available.iter().map(|(_, weight, _)| weight).sum();
+        let mut pick = rng.random_range(0.0..total);
+        let Some(&(action, _, seconds)) = available.iter().find(|(_, weight, _)| {
+            pick -= weight;
+            pick < 0.0
+        }) else {
+            continue;
+        };
+
+        // Face whatever the action is about.
+        let face = |target: f32, sprite: &mut Sprite| sprite.flip_x = target < x;
+        match action {
+            IdleAction::LookAround => sprite.flip_x = !sprite.flip_x,
+            IdleAction::WarmHands => face(fire.unwrap_or(x), &mut sprite),
+            IdleAction::WatchSnow => face(window.unwrap_or(x), &mut sprite),
+            IdleAction::Stretch | IdleAction::Dance | IdleAction::Shiver => {}
+        }
+
+        // Stretching and warming hands have their own frames.
+        let frame = match action {
+            IdleAction::Stretch => Some(STRETCH_FRAMES[0]),
+            IdleAction::WarmHands => Some(WARM_HANDS_FRAMES[0]),
+            IdleAction::LookAround | IdleAction::Dance | IdleAction::WatchSnow | IdleAction::Shiver => None,
+        };
+        if let Some(index) = frame {
+            sprite.image = sprite_assets.idle_sprite.clone();
+            sprite.texture_atlas = Some(TextureAtlas {
+                layout: sprite_assets.idle_layout.clone(),
+                index,
+            });
+        }
+
+        idle.playing = Some(IdlePlayback {
+            action,
+            timer: Timer::from_seconds(seconds, TimerMode::Once),
+            base_y: transform.translation.y,
+        });
     }
 }
 
@@ -358,19 +596,34 @@ fn handle_messages(
     }
 }
 
+// Stop walking when a dialogue, the menu, a cutscene or a door's fade takes over, since the key releases won't reach
+// the man until it's done.
+fn handle_input_blocked(mut commands: Commands, mut query: Query<(Entity, &mut State), With<TheMan>>) {
+    for (entity, mut state) in &mut query {
+        if *state == State::Walking {
+            *state = State::Idle;
+            commands.entity(entity).remove::<Navigation>();
+        }
+    }
+}
+
 // Move the man based on the current state.
 fn handle_movement(
     time: Res<Time>,
+    room: Res<CurrentState<Room>>,
     mut commands: Commands,
     query: Query<(Entity, &mut State, &Direction, &mut Transform, Option<&Navigation>), With<TheMan>>,
 ) {
+    let (left, right) = room.get().walk_range();
+
     for (entity, mut state, direction, mut transform, navigation) in query {
         match *state {
             State::Walking => {
-                // Check navigation status.
+                // Check navigation status, targets past the end of the room are reached at the end.
                 if let Some(target) = navigation
-                    && ((*direction == Direction::Left && transform.translation.x <= target.x)
-                        || (*direction == Direction::Right && transform.translation.x >= target.x))
+                    && let x = target.x.clamp(left, right)
+                    && ((*direction == Direction::Left && transform.translation.x <= x)
+                        || (*direction == Direction::Right && transform.translation.x >= x))
                 {
                     *state = if target.action { State::Action } else { State::Idle };
                     commands.entity(entity).remove::<Navigation>();
@@ -381,13 +634,13 @@ fn handle_movement(
                 match *direction {
                     Direction::Left => {
                         transform.translation.x -= WALKING_SPEED * time.delta_secs();
-                        transform.translation.x = transform.translation.x.max(-82.0);
+                        transform.translation.x = transform.translation.x.max(left);
                         transform.translation.z = 10.0;
                     }
 
                     Direction::Right => {
                         transform.translation.x += WALKING_SPEED * time.delta_secs();
-                        transform.translation.x = transform.translation.x.min(160.0);
+                        transform.translation.x = transform.translation.x.min(right);
                         transform.translation.z = 10.0;
                     }
 
@@ -414,33 +667,29 @@ fn init(
         sitting_layout: texture_layouts.add(TextureAtlasLayout::from_grid(UVec2::splat(32), 5, 1, None, None)),
         standing_sprite: asset_server.load("theman/theman_standing.png"),
         standing_layout: texture_layouts.add(TextureAtlasLayout::from_grid(UVec2::splat(32), 1, 1, None, None)),
+        idle_sprite: asset_server.load("theman/theman_idle_animation.png"),
+        idle_layout: texture_layouts.add(TextureAtlasLayout::from_grid(UVec2::splat(32), 4, 1, None, None)),
     };
     commands.insert_resource(sprites.clone());
 
-    // Load the sound effects.
-    let mut audio = AudioAssets {
-        left_steps: vec![],
-        right_steps: vec![],
-    };
-    audio
-        .left_steps
-        .push(asset_server.load("theman/left_footstep_indoor_1.ogg"));
-    audio
-        .left_steps
-        .push(asset_server.load("theman/left_footstep_indoor_2.ogg"));
-    audio
-        .left_steps
-        .push(asset_server.load("theman/left_footstep_indoor_3.ogg"));
-    audio
-        .right_steps
-        .push(asset_server.load("theman/right_footstep_indoor_1.ogg"));
-    audio
-        .right_steps
-        .push(asset_server.load("theman/right_footstep_indoor_2.ogg"));
-    audio
-        .right_steps
-        .push(asset_server.load("theman/right_footstep_indoor_3.ogg"));
-    commands.insert_resource(audio);
+    // Load the footstep sound effects for each surface.
+    let footsteps = FOOTSTEP_SETS
+        .iter()
+        .map(|&(surface, name, volume, speed)| {
+            let set = FootstepSet {
+                left: (1..=FOOTSTEP_VARIATIONS)
+                    .map(|i| asset_server.load(format!("theman/left_footstep_{name}_{i}.ogg")))
+                    .collect(),
+                right: (1..=FOOTSTEP_VARIATIONS)
+                    .map(|i| asset_server.load(format!("theman/right_footstep_{name}_{i}.ogg")))
+                    .collect(),
+                volume,
+                speed,
+            };
+            (surface, set)
+        })
+        .collect();
+    commands.insert_resource(AudioAssets { footsteps });
 
     // Create the man starting in the idle state.
     commands.spawn((
@@ -453,13 +702,17 @@ fn init(
             ..default()
         },
         Transform::from_translation(Vec3::new(-64.0, -56.0, 10.0)),
+        DespawnOnExit(CardState::Running),
         TheMan,
+        Actor("man"),
         AnimationConfig::new(0, 8, 10),
         State::Idle,
-        IdleTimer(Timer::from_seconds(5.0, TimerMode::Repeating)),
-        StepTimer(Timer::from_seconds(0.0, TimerMode::Repeating)),
+        Idle {
+            timer: Timer::from_seconds(IDLE_WAIT.0, TimerMode::Once),
+            playing: None,
+        },
         Direction::Right,
-        FootStep::Left,
+        FootContact::default(),
         // NOTE: not sure why the audio width needs to be negative to sound right.
         SpatialListener::new(-10.0),
         Interactor {
@@ -0,0 +1,591 @@
+use bevy::{asset::LoadState, ecs::system::SystemParam, platform::collections::HashMap, prelude::*};
+use serde::Deserialize;
+
+use crate::{
+    app::CardState,
+    camera::{ShakeEvent, ZoomEvent},
+    data,
+    dialogue::{Dialogue, DialogueRunner, DialogueState, StartDialogueEvent},
+    interaction::{self, SetStateEvent},
+    localization::Localized,
+    menu::MenuState,
+    settings::Settings,
+};
+
+// Whether a scripted sequence is playing, player input is blocked while it is.
+#[derive(States, Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum CutsceneState {
+    #[default]
+    Idle,
+    Playing,
+}
+
+// Scripted sequence of tracks authored as data, e.g. "santa/visit.timeline.ron".
+#[derive(Asset, TypePath, Deserialize)]
+pub struct Timeline {
+    #[serde(default = "skippable_default")]
+    pub skippable: bool,
+    pub tracks: Vec<Track>,
+}
+
+// A single part of a timeline, times are in seconds from the start of the timeline.
+#[derive(Deserialize)]
+pub enum Track {
+    // Play sprite sheet frames as (atlas index, seconds) on an actor, looping them until a time if given and
+    // hiding the actor afterwards if asked.
+    Clip {
+        actor: String,
+        start: f32,
+        image: String,
+        frame_size: (u32, u32),
+        columns: u32,
+        rows: u32,
+        frames: Vec<(usize, f32)>,
+        #[serde(default)]
+        loop_until: Option<f32>,
+        #[serde(default)]
+        flip_x: bool,
+        #[serde(default)]
+        hide_after: bool,
+    },
+    // Move an actor through (time, position) keys.
+    Move {
+        actor: String,
+        keys: Vec<(f32, (f32, f32))>,
+    },
+    // Play a sound, positioned at an actor if given.
+    Sound {
+        time: f32,
+        path: String,
+        #[serde(default)]
+        actor: Option<String>,
+    },
+    // Zoom the camera in on a point.
+    Camera {
+        time: f32,
+        target: (f32, f32),
+        zoom: f32,
+        duration: f32,
+    },
+    // Shake the camera.
+    Shake {
+        time: f32,
+        strength: f32,
+        duration: f32,
+    },
+    // Send a `TimelineEvent` for other systems to react to.
+    Event {
+        time: f32,
+        name: String,
+    },
+    // Turn an interactable on or off by its id, e.g. the fire going out.
+    SetState {
+        time: f32,
+        id: String,
+        state: interaction::State,
+    },
+    // Start a dialogue, the timeline waits while it's showing.
+    Dialogue {
+        time: f32,
+        path: String,
+        #[serde(default)]
+        node: Option<String>,
+    },
+}
+
+// Entity that timeline tracks can refer to by name.
+#[derive(Component)]
+pub struct Actor(pub &'static str);
+
+// Message sent to start playing a timeline.
+#[derive(Message)]
+pub struct PlayTimelineEvent(pub Handle<Timeline>);
+
+// Message sent by a timeline event track.
+#[derive(Message)]
+pub struct TimelineEvent(pub String);
+
+// A timeline being played.
+#[derive(Component)]
+struct Playback {
+    timeline: Handle<Timeline>,
+    // Time played up to this frame and the last frame, `None` before the timeline has loaded.
+    elapsed: Option<f32>,
+    previous: Option<f32>,
+    duration: f32,
+    skippable: bool,
+    skipped: bool,
+    // Sprite sheet for each clip track and dialogue for each dialogue track, loaded once the timeline has loaded.
+    clips: Vec<Option<(Handle<Image>, Handle<TextureAtlasLayout>)>>,
+    dialogues: Vec<Option<Handle<Dialogue>>>,
+}
+
+// Messages sent by the cue tracks.
+#[derive(SystemParam)]
+struct Cues<'w> {
+    events: MessageWriter<'w, TimelineEvent>,
+    states: MessageWriter<'w, SetStateEvent>,
+    dialogues: MessageWriter<'w, StartDialogueEvent>,
+    zooms: MessageWriter<'w, ZoomEvent>,
+    shakes: MessageWriter<'w, ShakeEvent>,
+}
+
+// On-screen button to skip the playing timelines.
+#[derive(Component)]
+struct SkipButton;
+
+const FONT_SIZE: f32 = 16.0;
+const BUTTON_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.1);
+
+fn skippable_default() -> bool {
+    true
+}
+
+impl Track {
+    // Time the track is done.
+    fn end(&self) -> f32 {
+        match self {
+            Self::Clip {
+                start,
+                frames,
+                loop_until,
+                ..
+            } => loop_until.unwrap_or(start + frames.iter().map(|(_, duration)| duration).sum::<f32>()),
+            Self::Move { keys, .. } => keys.last().map_or(0.0, |(time, _)| *time),
+            Self::Sound { time, .. }
+            | Self::Camera { time, .. }
+            | Self::Shake { time, .. }
+            | Self::Event { time, .. }
+            | Self::SetState { time, .. }
+            | Self::Dialogue { time, .. } => *time,
+        }
+    }
+}
+
+impl Playback {
+    // Whether a point in time was reached this frame.
+    fn crossed(&self, time: f32) -> bool {
+        self.elapsed.is_some_and(|elapsed| time <= elapsed) && self.previous.is_none_or(|previous| time > previous)
+    }
+}
+
+// Add the timeline systems.
+pub fn add_systems(app: &mut App) {
+    data::register::<Timeline>(app, &["timeline.ron"]);
+
+    app.init_state::<CutsceneState>()
+        .add_message::<PlayTimelineEvent>()
+        .add_message::<TimelineEvent>()
+        .add_systems(OnEnter(CutsceneState::Playing), spawn_skip_button)
+        .add_systems(
+            Update,
+            (
+                handle_play,
+                handle_skip.run_if(
+                    in_state(CutsceneState::Playing)
+                        .and(in_state(MenuState::Closed))
+                        .and(in_state(DialogueState::Closed)),
+                ),
+                handle_load,
+                handle_clock,
+                handle_cues,
+                handle_sounds,
+                handle_tracks,
+                handle_state,
+            )
+                .chain(),
+        );
+}
+
+// Load the clip sprite sheets and dialogues the first time a timeline is ready, so they're there when they're reached.
+fn handle_load(
+    asset_server: Res<AssetServer>,
+    timelines: Res<Assets<Timeline>>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut query: Query<&mut Playback>,
+) {
+    for mut playback in &mut query {
+        if playback.elapsed.is_some() {
+            continue;
+        }
+        let Some(timeline) = timelines.get(&playback.timeline) else {
+            continue;
+        };
+
+        playback.duration = timeline.tracks.iter().map(Track::end).fold(0.0, f32::max);
+        playback.skippable = timeline.skippable;
+        playback.clips = timeline
+            .tracks
+            .iter()
+            .map(|track| match track {
+                Track::Clip {
+                    image,
+                    frame_size: (width, height),
+                    columns,
+                    rows,
+                    ..
+                } => Some((
+                    asset_server.load(image),
+                    layouts.add(TextureAtlasLayout::from_grid(
+                        UVec2::new(*width, *height),
+                        *columns,
+                        *rows,
+                        None,
+                        None,
+                    )),
+                )),
+                _ => None,
+            })
+            .collect();
+        playback.dialogues = timeline
+            .tracks
+            .iter()
+            .map(|track| match track {
+                Track::Dialogue { path, .. } => Some(asset_server.load(path)),
+                _ => None,
+            })
+            .collect();
+    }
+}
+
+// Advance the playing timelines, waiting while a dialogue is starting or showing and removing them once they have
+// finished or failed to load.
+fn handle_clock(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    dialogue_state: Res<State<DialogueState>>,
+    runner: Res<DialogueRunner>,
+    timelines: Res<Assets<Timeline>>,
+    mut query: Query<(Entity, &mut Playback)>,
+) {
+    for (entity, mut playback) in &mut query {
+        if !timelines.contains(&playback.timeline) {
+            if let Some(LoadState::Failed(error)) = asset_server.get_load_state(&playback.timeline) {
+                warn!("couldn't load timeline: {error}");
+                commands.entity(entity).despawn();
+            }
+            continue;
+        }
+
+        if playback.elapsed.is_some_and(|elapsed| elapsed >= playback.duration) {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let delta = if *dialogue_state.get() == DialogueState::Open || runner.starting() {
+            0.0
+        } else {
+            time.delta_secs()
+        };
+        playback.previous = playback.elapsed;
+        playback.elapsed = Some(if playback.skipped {
+            playback.duration
+        } else {
+            playback.elapsed.map_or(0.0, |elapsed| elapsed + delta)
+        });
+    }
+}
+
+// Send the events, state changes, dialogues, camera moves and shakes reached this frame.
+// NOTE: skipping only sends the events and state changes so the sequence still has its effects.
+fn handle_cues(timelines: Res<Assets<Timeline>>, query: Query<&Playback>, mut cues: Cues) {
+    for playback in &query {
+        let Some(timeline) = timelines.get(&playback.timeline) else {
+            continue;
+        };
+
+        for (index, track) in timeline.tracks.iter().enumerate() {
+            match track {
+                Track::Event { time, name } if playback.crossed(*time) => {
+                    cues.events.write(TimelineEvent(name.clone()));
+                }
+
+                Track::SetState { time, id, state } if playback.crossed(*time) => {
+                    cues.states.write(SetStateEvent {
+                        id: id.clone(),
+                        state: *state,
+                    });
+                }
+
+                Track::Dialogue { time, node, .. } if playback.crossed(*time) && !playback.skipped => {
+                    let Some(Some(dialogue)) = playback.dialogues.get(index) else {
+                        continue;
+                    };
+                    cues.dialogues.write(StartDialogueEvent {
+                        dialogue: dialogue.clone(),
+                        node: node.clone(),
+                    });
+                }
+
+                Track::Camera {
+                    time,
+                    target: (x, y),
+                    zoom,
+                    duration,
+                } if playback.crossed(*time) && !playback.skipped => {
+                    cues.zooms.write(ZoomEvent {
+                        target: Vec2::new(*x, *y),
+                        zoom: *zoom,
+                        duration: *duration,
+                    });
+                }
+
+                Track::Camera { .. } if playback.skipped && playback.crossed(playback.duration) => {
+                    // End any zoom the timeline started.
+                    cues.zooms.write(ZoomEvent {
+                        target: Vec2::ZERO,
+                        zoom: 1.0,
+                        duration: 0.0,
+                    });
+                }
+
+                Track::Shake {
+                    time,
+                    strength,
+                    duration,
+                } if playback.crossed(*time) && !playback.skipped => {
+                    cues.shakes.write(ShakeEvent {
+                        strength: *strength,
+                        duration: *duration,
+                    });
+                }
+
+                _ => {}
+            }
+        }
+    }
+}
+
+// Start playing timelines.
+fn handle_play(mut commands: Commands, mut events: MessageReader<PlayTimelineEvent>) {
+    for event in events.read() {
+        commands.spawn((
+            Playback {
+                timeline: event.0.clone(),
+                elapsed: None,
+                previous: None,
+                duration: 0.0,
+                skippable: true,
+                skipped: false,
+                clips: Vec::new(),
+                dialogues: Vec::new(),
+            },
+            DespawnOnExit(CardState::Running),
+        ));
+    }
+}
+
+// Skip the playing timelines with the interact key, gamepad or skip button.
+fn handle_skip(
+    settings: Res<Settings>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    buttons: Query<&Interaction, (Changed<Interaction>, With<SkipButton>)>,
+    mut query: Query<&mut Playback>,
+) {
+    let skip = keyboard.just_pressed(settings.bindings.interact)
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.just_pressed(GamepadButton::South))
+        || buttons.iter().any(|interaction| *interaction == Interaction::Pressed);
+    if !skip {
+        return;
+    }
+
+    for mut playback in &mut query {
+        if playback.skippable {
+            playback.skipped = true;
+        }
+    }
+}
+
+// Play the sounds reached this frame.
+fn handle_sounds(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    timelines: Res<Assets<Timeline>>,
+    query: Query<&Playback>,
+    actors: Query<(Entity, &Actor)>,
+) {
+    for playback in &query {
+        let Some(timeline) = timelines.get(&playback.timeline) else {
+            continue;
+        };
+
+        for track in &timeline.tracks {
+            let Track::Sound { time, path, actor } = track else {
+                continue;
+            };
+            if !playback.crossed(*time) || playback.skipped {
+                continue;
+            }
+
+            let sound = (AudioPlayer::new(asset_server.load(path)), PlaybackSettings::DESPAWN);
+            match actor
+                .as_ref()
+                .and_then(|name| actors.iter().find(|(_, actor)| actor.0 == name))
+            {
+                Some((entity, _)) => {
+                    commands
+                        .entity(entity)
+                        .with_child((sound.0, sound.1.with_spatial(true)));
+                }
+                None => {
+                    commands.spawn(sound);
+                }
+            }
+        }
+    }
+}
+
+// Keep the cutscene state in step with the playing timelines.
+fn handle_state(
+    query: Query<(), With<Playback>>,
+    state: Res<State<CutsceneState>>,
+    mut next_state: ResMut<NextState<CutsceneState>>,
+) {
+    let playing = if query.is_empty() {
+        CutsceneState::Idle
+    } else {
+        CutsceneState::Playing
+    };
+    if *state.get() != playing {
+        next_state.set(playing);
+    }
+}
+
+// Pose the actors for the current time of their clip and movement tracks.
+// NOTE: only an actor's most recently started clip shows, it takes over from any earlier clip still going.
+fn handle_tracks(
+    timelines: Res<Assets<Timeline>>,
+    query: Query<&Playback>,
+    mut actors: Query<(&Actor, &mut Transform, &mut Sprite, &mut Visibility)>,
+) {
+    for playback in &query {
+        let (Some(timeline), Some(elapsed)) = (timelines.get(&playback.timeline), playback.elapsed) else {
+            continue;
+        };
+
+        // Latest clip started on each actor, later tracks win when clips start together.
+        let mut current: HashMap<&str, (f32, usize)> = HashMap::new();
+        for (index, track) in timeline.tracks.iter().enumerate() {
+            if let Track::Clip { actor, start, .. } = track
+                && elapsed >= *start
+                && current.get(actor.as_str()).is_none_or(|(latest, _)| *start >= *latest)
+            {
+                current.insert(actor, (*start, index));
+            }
+        }
+
+        for (index, (track, clip)) in timeline.tracks.iter().zip(&playback.clips).enumerate() {
+            match (track, clip) {
+                (
+                    Track::Clip {
+                        actor,
+                        start,
+                        frames,
+                        loop_until,
+                        flip_x,
+                        hide_after,
+                        ..
+                    },
+                    Some((image, layout)),
+                ) if current
+                    .get(actor.as_str())
+                    .is_some_and(|(_, current)| *current == index) =>
+                {
+                    let Some((_, _, mut sprite, mut visibility)) =
+                        actors.iter_mut().find(|(named, ..)| named.0 == actor)
+                    else {
+                        continue;
+                    };
+
+                    // Find the frame showing at this time, holding the last frame once the clip is done.
+                    let local = match loop_until {
+                        Some(until) if elapsed >= *until => f32::INFINITY,
+                        Some(_) => (elapsed - start).rem_euclid(frames.iter().map(|(_, duration)| duration).sum()),
+                        None => elapsed - start,
+                    };
+                    let frame = frames
+                        .iter()
+                        .scan(0.0, |end, (index, duration)| {
+                            *end += duration;
+                            Some((*index, *end))
+                        })
+                        .find(|(_, end)| local < *end)
+                        .map(|(index, _)| index);
+
+                    let index = match (frame, frames.last()) {
+                        (Some(index), _) => index,
+                        (None, _) if *hide_after => {
+                            visibility.set_if_neq(Visibility::Hidden);
+                            continue;
+                        }
+                        (None, Some((index, _))) => *index,
+                        (None, None) => continue,
+                    };
+
+                    visibility.set_if_neq(Visibility::Inherited);
+                    if sprite.flip_x != *flip_x {
+                        sprite.flip_x = *flip_x;
+                    }
+                    if sprite.image != *image || sprite.texture_atlas.as_ref().map(|atlas| atlas.index) != Some(index) {
+                        sprite.image = image.clone();
+                        sprite.texture_atlas = Some(TextureAtlas {
+                            layout: layout.clone(),
+                            index,
+                        });
+                    }
+                }
+
+                (Track::Move { actor, keys }, _) => {
+                    let Some((_, mut transform, ..)) = actors.iter_mut().find(|(named, ..)| named.0 == actor) else {
+                        continue;
+                    };
+
+                    // Interpolate between the keys around this time, holding still before and after them.
+                    let next = keys.iter().position(|(time, _)| *time > elapsed);
+                    let position = match next {
+                        Some(0) => continue,
+                        Some(next) => {
+                            let (start, from) = keys[next - 1];
+                            let (end, to) = keys[next];
+                            Vec2::from(from).lerp(Vec2::from(to), (elapsed - start) / (end - start))
+                        }
+                        None => match keys.last() {
+                            Some((_, position)) => Vec2::from(*position),
+                            None => continue,
+                        },
+                    };
+                    transform.translation.x = position.x;
+                    transform.translation.y = position.y;
+                }
+
+                _ => {}
+            }
+        }
+    }
+}
+
+// Show a skip button in the lower right while a timeline is playing.
+fn spawn_skip_button(mut commands: Commands) {
+    commands.spawn((
+        Button,
+        SkipButton,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: px(12),
+            right: px(12),
+            padding: UiRect::axes(px(6), px(2)),
+            ..default()
+        },
+        BackgroundColor(BUTTON_COLOR),
+        DespawnOnExit(CutsceneState::Playing),
+        children![(
+            Text::new("skip"),
+            Localized("timeline.skip"),
+            TextFont::from_font_size(FONT_SIZE)
+        )],
+    ));
+}
@@ -0,0 +1,234 @@
+use bevy::{ecs::system::SystemParam, prelude::*, window::PrimaryWindow};
+
+use crate::{
+    accessibility::display_name,
+    app::CardState,
+    dialogue::DialogueState,
+    interaction::{self, Hovered, InRange, Interactable},
+    localization::Localization,
+    menu::MenuState,
+    theman::TheMan,
+    timeline::CutsceneState,
+};
+
+// Tooltip describing an interactable, near the pointer while hovering it or above the man while he's in range.
+#[derive(Component)]
+struct Tooltip {
+    anchor: TooltipAnchor,
+    target: Option<Entity>,
+    // UI position the tooltip is anchored at.
+    position: Vec2,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum TooltipAnchor {
+    Pointer,
+    Man,
+}
+
+// Line of text inside a tooltip.
+#[derive(Component, Clone, Copy)]
+enum TooltipLine {
+    Name,
+    Description,
+    Prompt,
+}
+
+// Mouse pointer or first touch, in UI pixels.
+#[derive(SystemParam)]
+struct Pointer<'w, 's> {
+    window: Single<'w, 's, &'static Window, With<PrimaryWindow>>,
+    camera: Single<'w, 's, &'static Camera, With<Camera2d>>,
+    touches: Res<'w, Touches>,
+    ui_scale: Res<'w, UiScale>,
+}
+
+// Offset of the pointer tooltip from the pointer, in UI pixels.
+const POINTER_OFFSET: Vec2 = Vec2::new(10.0, 10.0);
+
+// Space between the top of the man and his tooltip, in world units.
+const MAN_GAP: f32 = 18.0;
+
+const NAME_FONT_SIZE: f32 = 11.0;
+const FONT_SIZE: f32 = 9.0;
+const BACKGROUND_COLOR: Color = Color::srgba(0.05, 0.05, 0.1, 0.85);
+const NAME_COLOR: Color = Color::srgb(1.0, 0.9, 0.6);
+const TEXT_COLOR: Color = Color::WHITE;
+const PROMPT_COLOR: Color = Color::srgb(0.7, 0.85, 1.0);
+
+impl Pointer<'_, '_> {
+    // Pointer position in the UI, which is laid out inside the letterboxed viewport rather than the whole window.
+    fn position(&self) -> Option<Vec2> {
+        let origin = self.camera.logical_viewport_rect().map_or(Vec2::ZERO, |rect| rect.min);
+        self.window
+            .cursor_position()
+            .or_else(|| self.touches.iter().next().map(|touch| touch.position()))
+            .map(|position| (position - origin) / self.ui_scale.0)
+    }
+}
+
+// Add the tooltip systems.
+pub fn add_systems(app: &mut App) {
+    app.add_systems(OnEnter(CardState::Running), init).add_systems(
+        Update,
+        (handle_pointer_target, handle_man_target, handle_tooltips).chain(),
+    );
+}
+
+// Short description of an interactable.
+pub fn description(localization: &Localization, id: &str) -> String {
+    localization.get(&format!("describe.{id}")).to_string()
+}
+
+// What interacting with an interactable will do in its current state, e.g. "turn on the tree".
+pub fn prompt(localization: &Localization, id: &str, state: interaction::State) -> String {
+    let state = match state {
+        interaction::State::On => "on",
+        interaction::State::Off => "off",
+    };
+    localization.get(&format!("prompt.{id}.{state}")).to_string()
+}
+
+// Follow the hovered interactable with the pointer tooltip.
+fn handle_pointer_target(
+    pointer: Pointer,
+    menu_state: Res<State<MenuState>>,
+    cutscene_state: Res<State<CutsceneState>>,
+    hovered: Query<Entity, (With<Interactable>, With<Hovered>)>,
+    mut tooltips: Query<&mut Tooltip>,
+) {
+    let pointer = pointer.position();
+    let visible = *menu_state.get() == MenuState::Closed && *cutscene_state.get() == CutsceneState::Idle;
+
+    for mut tooltip in &mut tooltips {
+        if tooltip.anchor != TooltipAnchor::Pointer {
+            continue;
+        }
+
+        tooltip.target = hovered.iter().next().filter(|_| visible && pointer.is_some());
+        if let Some(pointer) = pointer {
+            tooltip.position = pointer + POINTER_OFFSET;
+        }
+    }
+}
+
+// Show the interactable the man is in range of above him, unless the pointer tooltip already shows it.
+fn handle_man_target(
+    ui_scale: Res<UiScale>,
+    dialogue_state: Res<State<DialogueState>>,
+    cutscene_state: Res<State<CutsceneState>>,
+    camera: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+    man: Option<Single<&GlobalTransform, With<TheMan>>>,
+    in_range: Query<Entity, (With<Interactable>, With<InRange>)>,
+    mut tooltips: Query<&mut Tooltip>,
+) {
+    let pointer_target = tooltips
+        .iter()
+        .find(|tooltip| tooltip.anchor == TooltipAnchor::Pointer)
+        .and_then(|tooltip| tooltip.target);
+    let visible = *dialogue_state.get() == DialogueState::Closed && *cutscene_state.get() == CutsceneState::Idle;
+
+    // Place the tooltip above the man's head.
+    let (camera, camera_transform) = *camera;
+    let origin = camera.logical_viewport_rect().map_or(Vec2::ZERO, |rect| rect.min);
+    let position = man.and_then(|man| {
+        camera
+            .world_to_viewport(camera_transform, man.translation() + Vec3::Y * MAN_GAP)
+            .ok()
+            .map(|position| position - origin)
+    });
+
+    for mut tooltip in &mut tooltips {
+        if tooltip.anchor != TooltipAnchor::Man {
+            continue;
+        }
+
+        tooltip.target = in_range
+            .iter()
+            .find(|&entity| Some(entity) != pointer_target)
+            .filter(|_| visible && position.is_some());
+        if let Some(position) = position {
+            tooltip.position = position / ui_scale.0;
+        }
+    }
+}
+
+// Fill in and place the tooltips for their targets.
+fn handle_tooltips(
+    localization: Res<Localization>,
+    interactables: Query<(&Interactable, &interaction::State)>,
+    mut tooltips: Query<(&Tooltip, &mut Node, &mut Visibility, &Children)>,
+    mut lines: Query<(&TooltipLine, &mut Text)>,
+) {
+    for (tooltip, mut node, mut visibility, children) in &mut tooltips {
+        let Some((interactable, state)) = tooltip.target.and_then(|target| interactables.get(target).ok()) else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+
+        node.left = px(tooltip.position.x);
+        node.top = px(tooltip.position.y);
+        visibility.set_if_neq(Visibility::Inherited);
+
+        let mut lines = lines.iter_many_mut(children);
+        while let Some((line, mut text)) = lines.fetch_next() {
+            let value = match line {
+                TooltipLine::Name => display_name(&localization, &interactable.id),
+                TooltipLine::Description => description(&localization, &interactable.id),
+                TooltipLine::Prompt => prompt(&localization, &interactable.id, *state),
+            };
+            if text.0 != value {
+                text.0 = value;
+            }
+        }
+    }
+}
+
+// Tooltip initialization.
+fn init(mut commands: Commands) {
+    for (anchor, translation) in [
+        // The pointer tooltip hangs below and right of the pointer, the man's is centered above him.
+        (TooltipAnchor::Pointer, Val2::ZERO),
+        (TooltipAnchor::Man, Val2::percent(-50.0, -100.0)),
+    ] {
+        commands.spawn((
+            Tooltip {
+                anchor,
+                target: None,
+                position: Vec2::ZERO,
+            },
+            Node {
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                max_width: px(140),
+                padding: UiRect::axes(px(4), px(2)),
+                ..default()
+            },
+            UiTransform::from_translation(translation),
+            BackgroundColor(BACKGROUND_COLOR),
+            GlobalZIndex(4),
+            Visibility::Hidden,
+            DespawnOnExit(CardState::Running),
+            children![
+                (
+                    TooltipLine::Name,
+                    Text::new(""),
+                    TextFont::from_font_size(NAME_FONT_SIZE),
+                    TextColor(NAME_COLOR),
+                ),
+                (
+                    TooltipLine::Description,
+                    Text::new(""),
+                    TextFont::from_font_size(FONT_SIZE),
+                    TextColor(TEXT_COLOR),
+                ),
+                (
+                    TooltipLine::Prompt,
+                    Text::new(""),
+                    TextFont::from_font_size(FONT_SIZE),
+                    TextColor(PROMPT_COLOR),
+                ),
+            ],
+        ));
+    }
+}
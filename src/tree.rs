@@ -4,9 +4,12 @@ use rand::Rng;
 
 use crate::{
     animation::AnimationConfig,
+    app::CardState,
     flickering_light::FlickeringLight,
-    interaction::{Interactable, InteractionEvent, State},
-    santa::AddPresentsEvent,
+    interaction::{Interactable, InteractionEvent, State, StateSet},
+    particles::{Emitter, Shape},
+    rooms::{InRoom, Room},
+    santa::{AddPresentsEvent, Gift},
 };
 
 #[derive(Clone, Resource)]
@@ -15,14 +18,27 @@ struct SpriteAssets {
     on_layout: Handle<TextureAtlasLayout>,
     off_sprite: Handle<Image>,
     presents_sprite: Handle<Image>,
+    extra_presents_sprite: Handle<Image>,
+    coal_sprite: Handle<Image>,
 }
 
 #[derive(Component)]
 pub struct Tree;
 
+// Filter for the tree when its state changes.
+type ChangedTree = (With<Tree>, Changed<State>);
+
 #[derive(Component)]
 pub struct Presents;
 
+// Sparkles twinkling over the lit tree.
+#[derive(Component)]
+struct Sparkles;
+
+// Confetti thrown up when presents are left under the tree.
+#[derive(Component)]
+struct Confetti;
+
 const INTERACTABLE_ID: &str = "tree";
 
 // Light effect colors.
@@ -33,14 +49,31 @@ const LIGHT_COLORS: [Color; 4] = [
     Color::srgb(0.8, 0.8, 0.8),
 ];
 
+// Sparkle and confetti colors, and how much confetti is thrown up.
+const SPARKLE_TINTS: [Color; 3] = [
+    Color::srgb(1.0, 0.9, 0.5),
+    Color::srgb(1.0, 1.0, 1.0),
+    Color::srgb(0.7, 0.85, 1.0),
+];
+const CONFETTI_TINTS: [Color; 4] = [
+    Color::srgb(0.9, 0.15, 0.15),
+    Color::srgb(0.15, 0.75, 0.2),
+    Color::srgb(1.0, 0.85, 0.2),
+    Color::srgb(0.25, 0.45, 0.95),
+];
+const CONFETTI_COUNT: u32 = 40;
+
 // Add the animation systems.
 pub fn add_systems(app: &mut App) {
-    app.add_systems(Startup, init).add_systems(
+    app.add_systems(OnEnter(CardState::Running), init).add_systems(
         Update,
         (
             handle_animations,
-            handle_interaction,
-            handle_light.in_set(crate::flickering_light::LightInsertionSet),
+            handle_interaction.in_set(StateSet::Change),
+            (handle_sprite, handle_sparkles).in_set(StateSet::Effects),
+            handle_light
+                .in_set(StateSet::Effects)
+                .in_set(crate::flickering_light::LightInsertionSet),
             handle_presents_add,
         ),
     );
@@ -74,58 +107,47 @@ fn handle_animations(time: Res<Time>, mut query: Query<(&mut AnimationConfig, &m
 }
 
 // Listen for interaction events and update the tree state.
-fn handle_interaction(
-    sprite_assets: Res<SpriteAssets>,
-    mut events: MessageReader<InteractionEvent>,
-    mut query: Query<(&mut State, &mut Sprite), With<Tree>>,
-) {
+fn handle_interaction(mut events: MessageReader<InteractionEvent>, mut query: Query<&mut State, With<Tree>>) {
     for event in events.read() {
         if event.id == INTERACTABLE_ID
-            && let Ok((mut state, mut sprite)) = query.single_mut()
+            && let Ok(mut state) = query.single_mut()
         {
-            match *state {
-                State::Off => {
-                    *state = State::On;
-                    sprite.image = sprite_assets.on_sprite.clone();
-                    sprite.texture_atlas = Some(TextureAtlas {
-                        layout: sprite_assets.on_layout.clone(),
-                        index: 0,
-                    });
-                }
+            *state = match *state {
+                State::Off => State::On,
+                State::On => State::Off,
+            };
+        }
+    }
+}
 
-                State::On => {
-                    *state = State::Off;
-                    sprite.image = sprite_assets.off_sprite.clone();
-                    sprite.texture_atlas = None;
-                }
+// Swap between the running and off sprites when the state changes.
+fn handle_sprite(sprite_assets: Res<SpriteAssets>, mut query: Query<(&State, &mut Sprite), ChangedTree>) {
+    for (state, mut sprite) in &mut query {
+        match *state {
+            State::On => {
+                sprite.image = sprite_assets.on_sprite.clone();
+                sprite.texture_atlas = Some(TextureAtlas {
+                    layout: sprite_assets.on_layout.clone(),
+                    index: 0,
+                });
+            }
+
+            State::Off => {
+                sprite.image = sprite_assets.off_sprite.clone();
+                sprite.texture_atlas = None;
             }
         }
     }
 }
 
 // Add or remove a flickering light based on the tree state.
-fn handle_light(
-    mut commands: Commands,
-    mut query: Query<(Entity, &State, &mut PointLight2d), (With<Tree>, Changed<State>)>,
-) {
-    let mut rng = rand::rng();
-
+fn handle_light(mut commands: Commands, mut query: Query<(Entity, &State, &mut PointLight2d), ChangedTree>) {
     for (entity, state, mut light) in &mut query {
         match *state {
             State::On => {
-                commands.entity(entity).insert(FlickeringLight {
-                    seed: rng.random_range(0.0..1000.0),
-                    intensity_amplitude: 0.2,
-                    intensity_frequency: 1.0,
-                    intensity_min: 0.4,
-                    intensity_octaves: 3,
-                    color_frequency: 0.5,
-                    color_octaves: 3,
-                    color_seed_offset: 100.0,
-                    color_temperature: 0.5,
-                    colors: LIGHT_COLORS.to_vec(),
-                    time_offset: rng.random_range(0.0..100.0),
-                });
+                commands
+                    .entity(entity)
+                    .insert(FlickeringLight::new("candle", &LIGHT_COLORS));
             }
             State::Off => {
                 commands.entity(entity).remove::<FlickeringLight>();
@@ -135,34 +157,67 @@ fn handle_light(
     }
 }
 
-// Add presents sprite as a child entity to the tree when an AddPresentsEvent is received.
+// Twinkle the sparkles while the tree is lit.
+fn handle_sparkles(trees: Query<(&State, &Children), ChangedTree>, mut sparkles: Query<&mut Emitter, With<Sparkles>>) {
+    for (state, children) in &trees {
+        for child in children {
+            if let Ok(mut emitter) = sparkles.get_mut(*child) {
+                emitter.strength = if *state == State::On { 1.0 } else { 0.0 };
+            }
+        }
+    }
+}
+
+// Add the gift sprites as child entities to the tree when an AddPresentsEvent is received.
 fn handle_presents_add(
     mut commands: Commands,
     sprite_assets: Res<SpriteAssets>,
     mut events: MessageReader<AddPresentsEvent>,
     query: Query<(Entity, Option<&Children>), With<Tree>>,
     presents_query: Query<Entity, With<Presents>>,
+    mut confetti: Query<&mut Emitter, With<Confetti>>,
 ) {
-    for _event in events.read() {
+    for event in events.read() {
         for (entity, children) in query.iter() {
             let has_presents = children.map_or(false, |childs| {
                 childs.iter().any(|child| presents_query.contains(child))
             });
 
-            if !has_presents {
+            if has_presents {
+                continue;
+            }
+
+            let gifts = match event.0 {
+                Gift::Presents => vec![(&sprite_assets.presents_sprite, 20.0)],
+                Gift::Extra => vec![
+                    (&sprite_assets.presents_sprite, 20.0),
+                    (&sprite_assets.extra_presents_sprite, -20.0),
+                ],
+                Gift::Coal => vec![(&sprite_assets.coal_sprite, 20.0)],
+            };
+            for (image, x) in gifts {
                 let presents = commands
                     .spawn((
                         Sprite {
-                            image: sprite_assets.presents_sprite.clone(),
+                            image: image.clone(),
                             ..default()
                         },
-                        Transform::from_xyz(20.0, -24.0, 1.0),
+                        Transform::from_xyz(x, -24.0, 1.0),
                         Presents,
                     ))
                     .id();
 
                 commands.entity(entity).add_child(presents);
             }
+
+            // Celebrate presents with confetti, coal doesn't get any.
+            if event.0 != Gift::Coal {
+                for child in children.into_iter().flatten() {
+                    if let Ok(mut emitter) = confetti.get_mut(*child) {
+                        emitter.burst(CONFETTI_COUNT);
+                    }
+                }
+            }
         }
     }
 }
@@ -179,6 +234,8 @@ fn init(
         on_layout: texture_layouts.add(TextureAtlasLayout::from_grid(UVec2::splat(64), 5, 1, None, None)),
         off_sprite: asset_server.load("tree/tree.png"),
         presents_sprite: asset_server.load("tree/presents.png"),
+        extra_presents_sprite: asset_server.load("tree/presents_extra.png"),
+        coal_sprite: asset_server.load("tree/coal.png"),
     };
     commands.insert_resource(sprite.clone());
 
@@ -190,6 +247,8 @@ fn init(
             ..default()
         },
         Transform::from_translation(Vec3::new(-58.0, -38.0, 5.0)),
+        DespawnOnExit(CardState::Running),
+        InRoom(Room::Living),
         Tree,
         AnimationConfig::new(0, 4, 2),
         State::Off,
@@ -208,5 +267,29 @@ fn init(
             cast_shadows: true,
             ..default()
         },
+        children![
+            (
+                Sparkles,
+                Transform::from_xyz(0.0, 2.0, 0.5),
+                Emitter::new(10.0, Shape::Circle(18.0), (0.3, 0.8), (Vec2::ZERO, Vec2::ZERO))
+                    .with_color(Color::WHITE, Color::WHITE.with_alpha(0.0))
+                    .with_tints(&SPARKLE_TINTS),
+            ),
+            (
+                Confetti,
+                Transform::from_xyz(0.0, -24.0, 1.5),
+                Emitter::new(
+                    0.0,
+                    Shape::Box(Vec2::new(24.0, 2.0)),
+                    (1.5, 2.5),
+                    (Vec2::new(-25.0, 25.0), Vec2::new(25.0, 50.0)),
+                )
+                .with_gravity(60.0)
+                .with_wobble(10.0)
+                .with_size(2.0, 2.0)
+                .with_color(Color::WHITE, Color::WHITE.with_alpha(0.0))
+                .with_tints(&CONFETTI_TINTS),
+            ),
+        ],
     ));
 }
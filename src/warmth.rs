@@ -0,0 +1,123 @@
+use bevy::prelude::*;
+
+use crate::{
+    app::CardState,
+    fireplace::Fireplace,
+    particles::{Emitter, Shape},
+    rooms::Room,
+    snow::Snowfall,
+    theman::{Sitting, TheMan},
+};
+
+// How warm the man is, from 0 when he's freezing to 1 when he's toasty.
+#[derive(Resource)]
+pub struct Warmth(pub f32);
+
+// Breath the man puffs out into the cold, in front of his face.
+#[derive(Component)]
+struct Breath;
+
+// Warmth the man starts with and settles to indoors away from the fire.
+const START_WARMTH: f32 = 0.6;
+const ROOM_WARMTH: f32 = 0.5;
+
+// Below this the man is cold, shivering and not much fun for santa to visit.
+pub const COLD: f32 = 0.3;
+
+// Warmth gained or lost a second, by a fire burning at full heat, sitting in the chair, the heaviest snowfall
+// outside and settling toward the room's warmth.
+const FIRE_RATE: f32 = 1.0 / 20.0;
+const CHAIR_RATE: f32 = 1.0 / 60.0;
+const SNOW_RATE: f32 = 1.0 / 25.0;
+const ROOM_RATE: f32 = 1.0 / 120.0;
+
+// How close the man needs to be to the fire to feel it.
+const FIRE_DISTANCE: f32 = 60.0;
+
+// Breath puffs a second for a freezing man and where they come out from the middle of his sprite.
+const BREATH_RATE: f32 = 2.0;
+const BREATH_OFFSET: Vec3 = Vec3::new(4.0, 8.0, 0.1);
+const BREATH_COLORS: [Color; 2] = [Color::srgba(1.0, 1.0, 1.0, 0.6), Color::srgba(1.0, 1.0, 1.0, 0.0)];
+
+impl Default for Warmth {
+    fn default() -> Self {
+        Self(START_WARMTH)
+    }
+}
+
+// Add the warmth systems.
+pub fn add_systems(app: &mut App) {
+    app.init_resource::<Warmth>()
+        .add_systems(OnEnter(CardState::Running), init)
+        .add_systems(Update, (handle_warmth, handle_breath_spawn, handle_breath));
+}
+
+// Warm the man up by the fire and in the chair, and cool him down out in the snow.
+fn handle_warmth(
+    time: Res<Time>,
+    room: Res<State<Room>>,
+    snowfall: Res<Snowfall>,
+    mut warmth: ResMut<Warmth>,
+    man: Single<(&GlobalTransform, Has<Sitting>), With<TheMan>>,
+    fireplaces: Query<(&Fireplace, &GlobalTransform)>,
+) {
+    let (transform, sitting) = *man;
+    let x = transform.translation().x;
+    let delta = time.delta_secs();
+
+    let fire: f32 = fireplaces
+        .iter()
+        .filter(|(_, fireplace)| (fireplace.translation().x - x).abs() <= FIRE_DISTANCE)
+        .map(|(fireplace, _)| fireplace.heat() * FIRE_RATE)
+        .sum();
+    let chair = if sitting { CHAIR_RATE } else { 0.0 };
+
+    warmth.0 = match room.get() {
+        Room::Porch => warmth.0 - snowfall.0 * SNOW_RATE * delta,
+        Room::Living if fire + chair > 0.0 => (fire + chair).mul_add(delta, warmth.0),
+        // Away from anything warming him he settles to the warmth of the room.
+        _ => warmth.0 + (ROOM_WARMTH - warmth.0).clamp(-ROOM_RATE * delta, ROOM_RATE * delta),
+    }
+    .clamp(0.0, 1.0);
+}
+
+// Give the man breath to puff out.
+fn handle_breath_spawn(mut commands: Commands, query: Query<Entity, Added<TheMan>>) {
+    for entity in &query {
+        commands.entity(entity).with_child((
+            Breath,
+            Transform::from_translation(BREATH_OFFSET),
+            Emitter::new(
+                BREATH_RATE,
+                Shape::Point,
+                (1.0, 1.8),
+                (Vec2::new(-1.0, 1.0), Vec2::new(1.0, 3.0)),
+            )
+            .with_wind(1.0)
+            .with_size(1.0, 4.0)
+            .with_color(BREATH_COLORS[0], BREATH_COLORS[1]),
+        ));
+    }
+}
+
+// Show the man's breath outside, more of it the colder he is, in front of whichever way he's facing.
+fn handle_breath(
+    room: Res<State<Room>>,
+    warmth: Res<Warmth>,
+    man: Single<&Sprite, With<TheMan>>,
+    mut query: Query<(&mut Emitter, &mut Transform), With<Breath>>,
+) {
+    for (mut emitter, mut transform) in &mut query {
+        emitter.strength = if *room.get() == Room::Porch {
+            1.0 - warmth.0 / 2.0
+        } else {
+            0.0
+        };
+        transform.translation.x = if man.flip_x { -BREATH_OFFSET.x } else { BREATH_OFFSET.x };
+    }
+}
+
+// Every visit starts comfortably warm.
+fn init(mut warmth: ResMut<Warmth>) {
+    *warmth = Warmth::default();
+}
@@ -0,0 +1,158 @@
+use bevy::{platform::collections::HashSet, prelude::*, time::Stopwatch};
+
+use crate::{
+    app::CardState,
+    interaction::{State, StateSet},
+};
+
+// Circuits wired through the house, each powering the loads connected to it.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Circuit {
+    Attic,
+    Eaves,
+    Porch,
+}
+
+// Switch that powers its circuit while it's on.
+#[derive(Component)]
+pub struct Switch {
+    pub circuit: Circuit,
+}
+
+// Outlet passing power from its supply to its circuit, on a schedule for timer outlets.
+// NOTE: outlets without a supply are on the mains and always have power.
+#[derive(Component)]
+pub struct Outlet {
+    pub supply: Option<Circuit>,
+    pub circuit: Circuit,
+    pub schedule: Option<Schedule>,
+}
+
+// Timer repeatedly switching an outlet off and on, starting off.
+pub struct Schedule {
+    off: f32,
+    on: f32,
+    elapsed: Stopwatch,
+}
+
+// Load connected to a circuit, e.g. a light.
+#[derive(Component)]
+pub struct Wired {
+    pub circuit: Circuit,
+    pub powered: bool,
+}
+
+// Circuits and loads are updated for the frame, anything reacting to power runs after it.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WiringSet;
+
+// Circuits switched on and circuits with power.
+#[derive(Resource, Default)]
+struct Circuits {
+    switched: HashSet<Circuit>,
+    live: HashSet<Circuit>,
+}
+
+impl Schedule {
+    pub fn new(off: f32, on: f32) -> Self {
+        Self {
+            off,
+            on,
+            elapsed: Stopwatch::new(),
+        }
+    }
+
+    // Whether the timer is in the on part of its cycle.
+    fn is_on(&self) -> bool {
+        self.elapsed.elapsed_secs() % (self.off + self.on) >= self.off
+    }
+}
+
+impl Wired {
+    pub const fn new(circuit: Circuit) -> Self {
+        Self {
+            circuit,
+            powered: false,
+        }
+    }
+}
+
+// Add the wiring systems.
+pub fn add_systems(app: &mut App) {
+    app.init_resource::<Circuits>()
+        .add_systems(OnEnter(CardState::Running), init)
+        .configure_sets(Update, WiringSet.in_set(StateSet::Effects))
+        .add_systems(
+            Update,
+            (handle_switches, handle_circuits, handle_loads)
+                .chain()
+                .in_set(WiringSet),
+        );
+}
+
+// Work out which circuits have power from the switches and outlets.
+fn handle_circuits(time: Res<Time>, mut circuits: ResMut<Circuits>, mut outlets: Query<&mut Outlet>) {
+    let mut live = circuits.switched.clone();
+
+    for mut outlet in &mut outlets {
+        if let Some(schedule) = &mut outlet.schedule {
+            schedule.elapsed.tick(time.delta());
+        }
+    }
+
+    // Outlets can be plugged into each other's circuits, keep going until no more circuits get power.
+    loop {
+        let powered: Vec<Circuit> = outlets
+            .iter()
+            .filter(|outlet| !live.contains(&outlet.circuit))
+            .filter(|outlet| outlet.supply.is_none_or(|supply| live.contains(&supply)))
+            .filter(|outlet| outlet.schedule.as_ref().is_none_or(Schedule::is_on))
+            .map(|outlet| outlet.circuit)
+            .collect();
+        if powered.is_empty() {
+            break;
+        }
+        live.extend(powered);
+    }
+
+    if live != circuits.live {
+        circuits.live = live;
+    }
+}
+
+// Power or cut the loads when their circuit changes.
+fn handle_loads(circuits: Res<Circuits>, mut loads: Query<&mut Wired>) {
+    if !circuits.is_changed() {
+        return;
+    }
+
+    for mut wired in &mut loads {
+        let powered = circuits.live.contains(&wired.circuit);
+        if wired.powered != powered {
+            wired.powered = powered;
+        }
+    }
+}
+
+// Switch circuits on and off with their switches, however they were flipped.
+// NOTE: a circuit with more than one switch stays on while any of them is on.
+fn handle_switches(
+    mut circuits: ResMut<Circuits>,
+    changed: Query<(), (With<Switch>, Changed<State>)>,
+    switches: Query<(&Switch, &State)>,
+) {
+    if changed.is_empty() {
+        return;
+    }
+
+    circuits.switched = switches
+        .iter()
+        .filter(|(_, state)| **state == State::On)
+        .map(|(switch, _)| switch.circuit)
+        .collect();
+}
+
+// Every switch starts off when the card starts.
+fn init(mut circuits: ResMut<Circuits>) {
+    *circuits = Circuits::default();
+}